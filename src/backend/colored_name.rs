@@ -0,0 +1,171 @@
+/// One `^`-code from the BO3/T7 gamertag palette: the ten numbered colors,
+/// the `^;` rainbow cycle, and the `^:` team-color marker (resolved to each
+/// player's actual team color by the game client, not by this app).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorCode {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Cyan,
+    Pink,
+    White,
+    Grey,
+    Default,
+    Rainbow,
+    Team,
+}
+
+impl ColorCode {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '0' => ColorCode::Black,
+            '1' => ColorCode::Red,
+            '2' => ColorCode::Green,
+            '3' => ColorCode::Yellow,
+            '4' => ColorCode::Blue,
+            '5' => ColorCode::Cyan,
+            '6' => ColorCode::Pink,
+            '7' => ColorCode::White,
+            '8' => ColorCode::Grey,
+            '9' => ColorCode::Default,
+            ';' => ColorCode::Rainbow,
+            ':' => ColorCode::Team,
+            _ => return None,
+        })
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            ColorCode::Black => '0',
+            ColorCode::Red => '1',
+            ColorCode::Green => '2',
+            ColorCode::Yellow => '3',
+            ColorCode::Blue => '4',
+            ColorCode::Cyan => '5',
+            ColorCode::Pink => '6',
+            ColorCode::White => '7',
+            ColorCode::Grey => '8',
+            ColorCode::Default => '9',
+            ColorCode::Rainbow => ';',
+            ColorCode::Team => ':',
+        }
+    }
+
+    /// ANSI foreground escape for a terminal preview. `Rainbow` and `Team`
+    /// have no single static color, so they fall back to the default
+    /// terminal foreground.
+    fn ansi_escape(self) -> &'static str {
+        match self {
+            ColorCode::Black => "\x1b[30m",
+            ColorCode::Red => "\x1b[31m",
+            ColorCode::Green => "\x1b[32m",
+            ColorCode::Yellow => "\x1b[33m",
+            ColorCode::Blue => "\x1b[34m",
+            ColorCode::Cyan => "\x1b[36m",
+            ColorCode::Pink => "\x1b[35m",
+            ColorCode::White => "\x1b[37m",
+            ColorCode::Grey => "\x1b[90m",
+            ColorCode::Default | ColorCode::Rainbow | ColorCode::Team => "\x1b[39m",
+        }
+    }
+}
+
+/// A `playername` value tokenized into `(ColorCode, text)` segments, so a
+/// name with embedded or multiple color codes can be displayed, edited, and
+/// re-serialized without losing anything after the first `^` code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColoredName {
+    segments: Vec<(ColorCode, String)>,
+}
+
+impl ColoredName {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a segment of `text` in `color`, for front-ends building a
+    /// name piece by piece instead of concatenating escape codes by hand.
+    pub fn push(mut self, color: ColorCode, text: impl Into<String>) -> Self {
+        self.segments.push((color, text.into()));
+        self
+    }
+
+    /// Parses a raw `^`-encoded `playername` value into color segments.
+    /// Text before the first recognized `^` code is treated as `White`,
+    /// BO3's default gamertag color.
+    pub fn parse(raw: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut current_color = ColorCode::White;
+        let mut buf = String::new();
+        let mut chars = raw.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '^' {
+                if let Some(code) = chars.peek().and_then(|next| ColorCode::from_char(*next)) {
+                    if !buf.is_empty() {
+                        segments.push((current_color, std::mem::take(&mut buf)));
+                    }
+                    current_color = code;
+                    chars.next();
+                    continue;
+                }
+            }
+            buf.push(c);
+        }
+        if !buf.is_empty() {
+            segments.push((current_color, buf));
+        }
+
+        Self { segments }
+    }
+
+    /// The name with all color codes stripped, for plain display.
+    pub fn plain(&self) -> String {
+        self.segments.iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    /// Re-serializes back to the raw `^`-encoded form expected by
+    /// `t7patch.conf`. A leading `White` segment is left unprefixed, since
+    /// that's BO3's implicit default and `parse` never requires one either.
+    pub fn to_raw(&self) -> String {
+        let mut out = String::new();
+        let mut previous = ColorCode::White;
+        for (index, (color, text)) in self.segments.iter().enumerate() {
+            let needs_prefix = if index == 0 {
+                *color != ColorCode::White
+            } else {
+                *color != previous
+            };
+            if needs_prefix {
+                out.push('^');
+                out.push(color.to_char());
+            }
+            out.push_str(text);
+            previous = *color;
+        }
+        out
+    }
+
+    /// Renders the name with ANSI escapes for a terminal preview.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for (color, text) in &self.segments {
+            out.push_str(color.ansi_escape());
+            out.push_str(text);
+        }
+        out.push_str("\x1b[0m");
+        out
+    }
+
+    pub fn segments(&self) -> &[(ColorCode, String)] {
+        &self.segments
+    }
+}
+
+impl std::fmt::Display for ColoredName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.plain())
+    }
+}
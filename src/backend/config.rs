@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 use std::fs;
-use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use chrono::Local;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::logging::{LogCategory, log};
+use crate::logging::{log, LogCategory};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PresetEntry(pub String, pub String);
@@ -15,48 +15,227 @@ pub struct PresetEntry(pub String, pub String);
 #[derive(Debug, Clone, Deserialize)]
 pub struct Presets(pub HashMap<String, HashMap<String, (String, String)>>);
 
-pub fn update_config_values(
-    game_dir: &Path,
-    changes: &[(Regex, String)],
-    success_message: &str,
-) -> Result<()> {
+/// One line of `config.ini`. Blank lines, section headers (`[Name]`), and
+/// anything that isn't a recognized `key = "value"` entry are kept verbatim
+/// in `raw` so a round trip through [`ConfigDocument::render`] reproduces
+/// them byte-for-byte. Recognized entries additionally get their key, value,
+/// comment, and enclosing section parsed out so [`ConfigDocument::get`]/
+/// [`ConfigDocument::set`] can find and update them without touching
+/// anything else in the file.
+#[derive(Debug, Clone)]
+enum ConfigItem {
+    Raw(String),
+    Entry {
+        section: Option<String>,
+        key: String,
+        value: String,
+        comment: Option<String>,
+        raw: Option<String>,
+    },
+}
+
+/// A comment- and order-preserving parse of `config.ini`, so applying a
+/// preset can insert a missing key or update an existing one without
+/// clobbering inline comments or reshuffling the rest of the file the way
+/// whole-line regex replacement did.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigDocument {
+    items: Vec<ConfigItem>,
+}
+
+impl ConfigDocument {
+    pub fn parse(contents: &str) -> Self {
+        let entry_pattern =
+            Regex::new(r#"^\s*([A-Za-z0-9_]+)\s*=\s*"([^"]*)"\s*(?://\s*(.*))?$"#).unwrap();
+        let mut section: Option<String> = None;
+        let mut items = Vec::new();
+
+        for line in contents.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                section = Some(trimmed[1..trimmed.len() - 1].to_string());
+                items.push(ConfigItem::Raw(line.to_string()));
+                continue;
+            }
+            if let Some(caps) = entry_pattern.captures(line) {
+                items.push(ConfigItem::Entry {
+                    section: section.clone(),
+                    key: caps[1].to_string(),
+                    value: caps[2].to_string(),
+                    comment: caps.get(3).map(|m| m.as_str().to_string()),
+                    raw: Some(line.to_string()),
+                });
+                continue;
+            }
+            items.push(ConfigItem::Raw(line.to_string()));
+        }
+
+        Self { items }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for item in &self.items {
+            match item {
+                ConfigItem::Raw(line) => out.push_str(line),
+                ConfigItem::Entry {
+                    key,
+                    value,
+                    comment,
+                    raw,
+                    ..
+                } => match raw {
+                    Some(line) => out.push_str(line),
+                    None => out.push_str(&render_entry(key, value, comment.as_deref())),
+                },
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.items.iter().find_map(|item| match item {
+            ConfigItem::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Updates `key` in place if it already exists (preserving its section
+    /// and position), otherwise appends a new entry under the document's
+    /// last section, creating a `[Settings]` section first if the file has
+    /// none at all.
+    pub fn set(&mut self, key: &str, value: &str, comment: &str) {
+        let comment = if comment.is_empty() {
+            None
+        } else {
+            Some(comment.to_string())
+        };
+
+        for item in &mut self.items {
+            if let ConfigItem::Entry {
+                key: k,
+                value: v,
+                comment: c,
+                raw,
+                ..
+            } = item
+            {
+                if k == key {
+                    *v = value.to_string();
+                    *c = comment;
+                    *raw = None;
+                    return;
+                }
+            }
+        }
+
+        let section = self.last_section().or_else(|| {
+            self.items.push(ConfigItem::Raw("[Settings]".to_string()));
+            Some("Settings".to_string())
+        });
+        self.items.push(ConfigItem::Entry {
+            section,
+            key: key.to_string(),
+            value: value.to_string(),
+            comment,
+            raw: None,
+        });
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.items
+            .retain(|item| !matches!(item, ConfigItem::Entry { key: k, .. } if k == key));
+    }
+
+    fn last_section(&self) -> Option<String> {
+        self.items.iter().rev().find_map(|item| match item {
+            ConfigItem::Raw(line) => {
+                let trimmed = line.trim();
+                if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                    Some(trimmed[1..trimmed.len() - 1].to_string())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
+}
+
+fn render_entry(key: &str, value: &str, comment: Option<&str>) -> String {
+    match comment {
+        Some(comment) => format!("{} = \"{}\" // {}", key, value, comment),
+        None => format!("{} = \"{}\"", key, value),
+    }
+}
+
+fn load_config_document(game_dir: &Path) -> Result<ConfigDocument> {
     let config_path = config_ini(game_dir);
     if !config_path.exists() {
         anyhow::bail!("config.ini not found at {}", config_path.display());
     }
+    let contents = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+    Ok(ConfigDocument::parse(&contents))
+}
 
-    let mut contents = String::new();
-    fs::File::open(&config_path)?.read_to_string(&mut contents)?;
-    let mut lines: Vec<String> = contents.lines().map(|s| s.to_string()).collect();
+fn save_config_document(game_dir: &Path, document: &ConfigDocument) -> Result<()> {
+    let config_path = config_ini(game_dir);
+    fs::write(&config_path, document.render())
+        .with_context(|| format!("Failed to write {}", config_path.display()))
+}
 
-    for line in &mut lines {
-        for (pattern, replacement) in changes {
-            if pattern.is_match(line) {
-                *line = replacement.clone();
-                break;
-            }
+pub fn set_config_value(
+    game_dir: &Path,
+    mod_dir: &Path,
+    key: &str,
+    value: &str,
+    comment: &str,
+) -> Result<()> {
+    let mut changes = HashMap::new();
+    changes.insert(key.to_string(), (value.to_string(), comment.to_string()));
+    let backup = create_backup(game_dir, mod_dir, None, &changes)?;
+
+    let result: Result<()> = (|| {
+        let mut document = load_config_document(game_dir)?;
+        document.set(key, value, comment);
+        save_config_document(game_dir, &document)?;
+        if load_config_document(game_dir)?.get(key) != Some(value) {
+            anyhow::bail!("config.ini did not contain {} after writing it", key);
         }
-    }
+        Ok(())
+    })();
 
-    let mut file = fs::File::create(&config_path)?;
-    for line in lines {
-        writeln!(file, "{}", line)?;
+    if let Err(err) = result {
+        restore_from(game_dir, &backup)?;
+        log(
+            LogCategory::Warning,
+            format!("Failed to set {}; config.ini was rolled back", key),
+        );
+        return Err(err);
     }
-    log(LogCategory::Success, success_message);
+
+    log(LogCategory::Success, format!("Set {} to {}", key, value));
     Ok(())
 }
 
-pub fn set_config_value(game_dir: &Path, key: &str, value: &str, comment: &str) -> Result<()> {
-    let pattern = Regex::new(&format!(r"^\s*{}\s*=", regex::escape(key)))?;
-    let replacement = format!("{} = \"{}\" // {}", key, value, comment);
-    update_config_values(
-        game_dir,
-        &[(pattern, replacement)],
-        &format!("Set {} to {}", key, value),
-    )
+pub fn toggle_stutter_reduction(game_dir: &Path, mod_dir: &Path, enable: bool) -> Result<()> {
+    let mut changes = HashMap::new();
+    changes.insert(
+        "ReduceStutter".to_string(),
+        (if enable { "1" } else { "0" }.to_string(), String::new()),
+    );
+    let backup = create_backup(game_dir, mod_dir, None, &changes)?;
+
+    if let Err(err) = toggle_stutter_dll(game_dir, enable) {
+        restore_from(game_dir, &backup)?;
+        return Err(err);
+    }
+    Ok(())
 }
 
-pub fn toggle_stutter_reduction(game_dir: &Path, enable: bool) -> Result<()> {
+fn toggle_stutter_dll(game_dir: &Path, enable: bool) -> Result<()> {
     let dll_file = game_dir.join("d3dcompiler_46.dll");
     let dll_bak = dll_file.with_extension("dll.bak");
     if enable {
@@ -109,40 +288,179 @@ pub fn load_presets(path: &Path) -> Result<HashMap<String, HashMap<String, (Stri
 
 pub fn apply_preset(
     game_dir: &Path,
+    mod_dir: &Path,
     preset_name: &str,
     presets: &HashMap<String, HashMap<String, (String, String)>>,
 ) -> Result<()> {
     let preset = presets
         .get(preset_name)
         .ok_or_else(|| anyhow::anyhow!("Preset {} not found", preset_name))?;
+    let backup = create_backup(game_dir, mod_dir, Some(preset_name), preset)?;
+
+    let result: Result<()> = (|| {
+        let mut document = load_config_document(game_dir)?;
+        for (key, (value, comment)) in preset {
+            if key == "ReduceStutter" {
+                toggle_stutter_dll(game_dir, value == "1")?;
+                continue;
+            }
+            document.set(key, value, comment);
+            if key == "BackbufferCount" && value == "3" {
+                document.set("Vsync", "1", "Enabled with triple-buffered V-sync");
+            }
+        }
+        save_config_document(game_dir, &document)?;
+
+        let reparsed = load_config_document(game_dir)?;
+        for (key, (value, _)) in preset {
+            if key == "ReduceStutter" {
+                continue;
+            }
+            if reparsed.get(key) != Some(value.as_str()) {
+                anyhow::bail!(
+                    "config.ini did not contain {} after applying the preset",
+                    key
+                );
+            }
+        }
+        Ok(())
+    })();
+
+    if let Err(err) = result {
+        restore_from(game_dir, &backup)?;
+        log(
+            LogCategory::Warning,
+            format!(
+                "Failed to apply preset '{}'; config.ini was rolled back",
+                preset_name
+            ),
+        );
+        return Err(err);
+    }
+
+    log(
+        LogCategory::Success,
+        format!("Applied preset '{}'", preset_name),
+    );
+    Ok(())
+}
+
+/// A timestamped snapshot of `config.ini` and the stutter-reduction DLL
+/// rename state, taken before a mutating operation so it can be undone.
+/// `changes` reuses the same value/comment pairs as [`Presets`] to leave a
+/// human-readable record of what the operation was about to change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBackup {
+    pub id: String,
+    pub preset: Option<String>,
+    pub changes: HashMap<String, (String, String)>,
+}
+
+fn backups_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("config_backups")
+}
+
+fn manifest_path(backup_dir: &Path) -> PathBuf {
+    backup_dir.join("manifest.json")
+}
+
+fn create_backup(
+    game_dir: &Path,
+    mod_dir: &Path,
+    preset: Option<&str>,
+    changes: &HashMap<String, (String, String)>,
+) -> Result<PathBuf> {
+    let id = Local::now().format("%Y%m%d-%H%M%S%3f").to_string();
+    let backup_dir = backups_dir(mod_dir).join(&id);
+    fs::create_dir_all(&backup_dir)
+        .with_context(|| format!("Failed to create {}", backup_dir.display()))?;
+
     let config_path = config_ini(game_dir);
-    if !config_path.exists() {
-        anyhow::bail!("config.ini not found at {}", config_path.display());
+    if config_path.exists() {
+        fs::copy(&config_path, backup_dir.join("config.ini"))?;
+    }
+    let dll_file = game_dir.join("d3dcompiler_46.dll");
+    let dll_bak = dll_file.with_extension("dll.bak");
+    if dll_file.exists() {
+        fs::copy(&dll_file, backup_dir.join("d3dcompiler_46.dll"))?;
+    } else if dll_bak.exists() {
+        fs::copy(&dll_bak, backup_dir.join("d3dcompiler_46.dll.bak"))?;
     }
 
-    let mut changes: Vec<(Regex, String)> = Vec::new();
-    for (key, (value, comment)) in preset {
-        if key == "ReduceStutter" {
-            toggle_stutter_reduction(game_dir, value == "1")?;
+    let manifest = ConfigBackup {
+        id: id.clone(),
+        preset: preset.map(str::to_string),
+        changes: changes.clone(),
+    };
+    fs::write(
+        manifest_path(&backup_dir),
+        serde_json::to_string_pretty(&manifest)?,
+    )
+    .with_context(|| format!("Failed to write {}", manifest_path(&backup_dir).display()))?;
+
+    Ok(backup_dir)
+}
+
+/// Copies `config.ini` and the stutter DLL state back out of `backup_dir`.
+/// A backup holding neither the DLL nor its renamed `.dll.bak` means neither
+/// existed when it was taken, so any live or renamed copy is removed to match.
+fn restore_from(game_dir: &Path, backup_dir: &Path) -> Result<()> {
+    let backup_config = backup_dir.join("config.ini");
+    if backup_config.exists() {
+        fs::copy(&backup_config, config_ini(game_dir))?;
+    }
+
+    let dll_file = game_dir.join("d3dcompiler_46.dll");
+    let dll_bak = dll_file.with_extension("dll.bak");
+    let backup_dll = backup_dir.join("d3dcompiler_46.dll");
+    let backup_dll_bak = backup_dir.join("d3dcompiler_46.dll.bak");
+    if backup_dll.exists() {
+        let _ = fs::remove_file(&dll_bak);
+        fs::copy(&backup_dll, &dll_file)?;
+    } else if backup_dll_bak.exists() {
+        let _ = fs::remove_file(&dll_file);
+        fs::copy(&backup_dll_bak, &dll_bak)?;
+    } else {
+        let _ = fs::remove_file(&dll_file);
+        let _ = fs::remove_file(&dll_bak);
+    }
+    Ok(())
+}
+
+/// Lists config backups newest-first, for a history view of past preset/key
+/// changes.
+pub fn list_backups(mod_dir: &Path) -> Result<Vec<ConfigBackup>> {
+    let dir = backups_dir(mod_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
             continue;
         }
-        let pattern = Regex::new(&format!(r"^\s*{}\s*=", regex::escape(key)))?;
-        let replacement = format!("{} = \"{}\" // {}", key, value, comment);
-        changes.push((pattern, replacement));
-        if key == "BackbufferCount" && value == "3" {
-            let vsync_pattern = Regex::new(r"^\s*Vsync\s*=")?;
-            changes.push((
-                vsync_pattern,
-                "Vsync = \"1\" // Enabled with triple-buffered V-sync".to_string(),
-            ));
+        if let Ok(data) = fs::read_to_string(manifest_path(&entry.path())) {
+            if let Ok(backup) = serde_json::from_str(&data) {
+                backups.push(backup);
+            }
         }
     }
+    backups.sort_by(|a: &ConfigBackup, b: &ConfigBackup| b.id.cmp(&a.id));
+    Ok(backups)
+}
 
-    update_config_values(
-        game_dir,
-        &changes,
-        &format!("Applied preset '{}'", preset_name),
-    )
+pub fn restore_backup(game_dir: &Path, mod_dir: &Path, id: &str) -> Result<()> {
+    let backup_dir = backups_dir(mod_dir).join(id);
+    if !backup_dir.exists() {
+        anyhow::bail!("No config backup found with id {}", id);
+    }
+    restore_from(game_dir, &backup_dir)?;
+    log(
+        LogCategory::Success,
+        format!("Restored config backup {}", id),
+    );
+    Ok(())
 }
 
 #[derive(Debug, Default, Clone)]
@@ -169,32 +487,28 @@ pub fn check_essential_status(game_dir: &Path) -> Result<EssentialStatus> {
         return Ok(EssentialStatus::default());
     }
 
-    let content = fs::read_to_string(&config_path)?;
+    let contents = fs::read_to_string(&config_path)?;
+    let document = ConfigDocument::parse(&contents);
     let mut status = EssentialStatus::default();
 
-    status.max_fps = capture_int(&content, r#"MaxFPS\s*=\s*"([^"]+)""#, 165);
-    status.fov = capture_int(&content, r#"FOV\s*=\s*"([^"]+)""#, 80);
-    status.display_mode = capture_int(&content, r#"FullScreenMode\s*=\s*"([^"]+)""#, 1);
-    status.resolution = capture_string(
-        &content,
-        r#"WindowSize\s*=\s*"([^"]+)""#,
-        "2560x1440".into(),
-    );
-    status.refresh_rate = capture_float(&content, r#"RefreshRate\s*=\s*"([^"]+)""#, 165.0);
-    status.vsync = capture_bool(&content, r#"Vsync\s*=\s*"([^"]+)""#, true);
-    status.draw_fps = capture_bool(&content, r#"DrawFPS\s*=\s*"([^"]+)""#, false);
-    status.all_settings = capture_bool(
-        &content,
-        r#"RestrictGraphicsOptions\s*=\s*"([^"]+)""#,
-        false,
-    );
-    status.smooth = capture_bool(&content, r#"SmoothFramerate\s*=\s*"([^"]+)""#, false);
-    let vram_enabled = capture_string(&content, r#"VideoMemory\s*=\s*"([^"]+)""#, "1".into());
-    let stream_min = capture_string(&content, r#"StreamMinResident\s*=\s*"([^"]+)""#, "0".into());
+    status.max_fps = parse_or(document.get("MaxFPS"), 165);
+    status.fov = parse_or(document.get("FOV"), 80);
+    status.display_mode = parse_or(document.get("FullScreenMode"), 1);
+    status.resolution = document
+        .get("WindowSize")
+        .map(str::to_string)
+        .unwrap_or_else(|| "2560x1440".to_string());
+    status.refresh_rate = parse_or(document.get("RefreshRate"), 165.0);
+    status.vsync = is_enabled(document.get("Vsync"), true);
+    status.draw_fps = is_enabled(document.get("DrawFPS"), false);
+    status.all_settings = is_enabled(document.get("RestrictGraphicsOptions"), false);
+    status.smooth = is_enabled(document.get("SmoothFramerate"), false);
+    let vram_enabled = document.get("VideoMemory").unwrap_or("1");
+    let stream_min = document.get("StreamMinResident").unwrap_or("0");
     status.vram = !(vram_enabled == "1" && stream_min == "0");
     status.vram_value = vram_enabled.parse().unwrap_or(0.75);
-    status.latency = capture_int(&content, r#"MaxFrameLatency\s*=\s*"([^"]+)""#, 1);
-    status.reduce_cpu = capture_bool(&content, r#"SerializeRender\s*=\s*"([^"]+)""#, false);
+    status.latency = parse_or(document.get("MaxFrameLatency"), 1);
+    status.reduce_cpu = is_enabled(document.get("SerializeRender"), false);
     let intro_bak = game_dir
         .join("video")
         .join("BO3_Global_Logo_LogoSequence.mkv.bak");
@@ -207,38 +521,10 @@ fn config_ini(game_dir: &Path) -> PathBuf {
     game_dir.join("players").join("config.ini")
 }
 
-fn capture_int(content: &str, pattern: &str, default: i32) -> i32 {
-    Regex::new(pattern)
-        .ok()
-        .and_then(|re| re.captures(content))
-        .and_then(|caps| caps.get(1))
-        .and_then(|m| m.as_str().parse().ok())
-        .unwrap_or(default)
-}
-
-fn capture_float(content: &str, pattern: &str, default: f32) -> f32 {
-    Regex::new(pattern)
-        .ok()
-        .and_then(|re| re.captures(content))
-        .and_then(|caps| caps.get(1))
-        .and_then(|m| m.as_str().parse().ok())
-        .unwrap_or(default)
-}
-
-fn capture_string(content: &str, pattern: &str, default: String) -> String {
-    Regex::new(pattern)
-        .ok()
-        .and_then(|re| re.captures(content))
-        .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str().to_string())
-        .unwrap_or(default)
-}
-
-fn capture_bool(content: &str, pattern: &str, default: bool) -> bool {
-    Regex::new(pattern)
-        .ok()
-        .and_then(|re| re.captures(content))
-        .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str() == "1")
-        .unwrap_or(default)
+fn parse_or<T: std::str::FromStr>(value: Option<&str>, default: T) -> T {
+    value.and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn is_enabled(value: Option<&str>, default: bool) -> bool {
+    value.map(|v| v == "1").unwrap_or(default)
 }
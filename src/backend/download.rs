@@ -0,0 +1,116 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+
+use crate::backend::progress::{self, ProgressSender};
+
+/// Callback surface for a download in progress, letting a GUI/CLI render a
+/// progress bar and cooperatively cancel mid-transfer.
+pub trait DownloadProgress {
+    /// Called after each chunk with the bytes received so far and the total
+    /// size if the server reported one (0 if unknown).
+    fn on_progress(&self, bytes_done: u64, total: u64);
+
+    /// Polled between chunks; returning `true` aborts the download, leaving
+    /// the partial `.part` file in place for a later resume.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Bridges `DownloadProgress` into the flume-channel progress model the rest
+/// of the app already uses, and polls an optional shared cancel flag.
+pub struct ChannelProgress<'a> {
+    pub sender: Option<&'a ProgressSender>,
+    pub label: &'a str,
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+impl<'a> DownloadProgress for ChannelProgress<'a> {
+    fn on_progress(&self, bytes_done: u64, total: u64) {
+        progress::report(self.sender, self.label, bytes_done, total);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .map(|flag| flag.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+}
+
+/// Downloads `url` to `dest`, resuming from a `<dest>.part` file left behind
+/// by an earlier interrupted attempt via HTTP `Range`, and only renaming it
+/// to `dest` once the body is fully received — so a cancelled or dropped
+/// download never leaves a corrupt file at the final path.
+pub fn download_resumable(dest: &Path, url: &str, progress: &dyn DownloadProgress) -> Result<PathBuf> {
+    let part_path = part_path_for(dest);
+    let existing_len = fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+
+    let client = reqwest::blocking::Client::new();
+    let mut request = client.get(url);
+    if existing_len > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing_len));
+    }
+    let mut response = request
+        .send()
+        .with_context(|| format!("Failed to download {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to download {}: {}", url, response.status());
+    }
+
+    let (mut file, mut downloaded, total) =
+        if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            let total = response
+                .content_length()
+                .map(|remaining| existing_len + remaining)
+                .unwrap_or(0);
+            let file = OpenOptions::new()
+                .append(true)
+                .open(&part_path)
+                .with_context(|| format!("Failed to resume {}", part_path.display()))?;
+            (file, existing_len, total)
+        } else {
+            // Either there was nothing to resume, or the server ignored our Range
+            // header and is sending the whole body again: start the part file over.
+            let file = File::create(&part_path)
+                .with_context(|| format!("Failed to create {}", part_path.display()))?;
+            (file, 0, response.content_length().unwrap_or(0))
+        };
+
+    progress.on_progress(downloaded, total);
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        if progress.is_cancelled() {
+            anyhow::bail!("Download of {} was cancelled", url);
+        }
+        let read = response.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buf[..read])?;
+        downloaded += read as u64;
+        progress.on_progress(downloaded, total);
+    }
+
+    fs::rename(&part_path, dest)
+        .with_context(|| format!("Failed to finalize download to {}", dest.display()))?;
+    Ok(dest.to_path_buf())
+}
+
+fn part_path_for(dest: &Path) -> PathBuf {
+    let file_name = dest.file_name().unwrap_or_default().to_string_lossy();
+    dest.with_file_name(format!("{}.part", file_name))
+}
+
+/// The last path segment of `url`, used as the destination filename when the
+/// caller doesn't already have one in mind.
+pub fn derive_filename(url: &str) -> String {
+    url.rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty())
+        .unwrap_or("download")
+        .to_string()
+}
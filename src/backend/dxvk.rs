@@ -1,16 +1,24 @@
+use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
 use tar::Archive;
 use walkdir::WalkDir;
 
+use crate::backend::download::{self, ChannelProgress};
+use crate::backend::progress::{self, ProgressSender};
+use crate::backend::transaction::InstallTransaction;
+use crate::backend::verify::hash_file;
 use crate::logging::{LogCategory, log};
 
 const DXVK_ASYNC_FILES: [&str; 2] = ["dxgi.dll", "d3d11.dll"];
 const DXVK_RELEASE_API: &str = "https://gitlab.com/api/v4/projects/Ph42oN%2Fdxvk-gplasync/releases";
+const MANIFEST_FILE: &str = "manifest.json";
 
 pub fn is_installed(game_dir: &Path) -> bool {
     DXVK_ASYNC_FILES
@@ -18,7 +26,25 @@ pub fn is_installed(game_dir: &Path) -> bool {
         .all(|file| game_dir.join(file).exists())
 }
 
-pub fn uninstall(game_dir: &Path) -> Result<()> {
+/// The DXVK DLL names (without extension) that need a native/builtin
+/// override in a Wine prefix for the installed DLLs in `game_dir` to load.
+pub fn override_dll_names() -> impl Iterator<Item = &'static str> {
+    DXVK_ASYNC_FILES
+        .iter()
+        .map(|file| file.trim_end_matches(".dll"))
+}
+
+/// Which of the DXVK DLLs are missing from `game_dir`, for front-ends that
+/// want to report exactly what's absent rather than a single boolean.
+pub fn missing_files(game_dir: &Path) -> Vec<String> {
+    DXVK_ASYNC_FILES
+        .iter()
+        .filter(|file| !game_dir.join(file).exists())
+        .map(|file| file.to_string())
+        .collect()
+}
+
+pub fn uninstall(game_dir: &Path, mod_dir: &Path) -> Result<()> {
     if !is_installed(game_dir) {
         log(LogCategory::Info, "DXVK-GPLAsync is not installed");
         return Ok(());
@@ -36,10 +62,76 @@ pub fn uninstall(game_dir: &Path) -> Result<()> {
         fs::remove_file(&conf_path).ok();
         log(LogCategory::Success, "Removed dxvk.conf");
     }
+
+    let mut manifest = load_manifest(mod_dir).unwrap_or_default();
+    manifest.active_tag = None;
+    save_manifest(mod_dir, &manifest).ok();
+
     log(LogCategory::Success, "DXVK-GPLAsync has been uninstalled");
     Ok(())
 }
 
+/// The on-disk cache directory under `mod_dir` holding one subfolder per
+/// cached DXVK-GPLAsync release, plus the manifest recording what's active.
+fn cache_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("dxvk")
+}
+
+fn version_dir(mod_dir: &Path, tag: &str) -> PathBuf {
+    cache_dir(mod_dir).join(tag)
+}
+
+fn manifest_path(mod_dir: &Path) -> PathBuf {
+    cache_dir(mod_dir).join(MANIFEST_FILE)
+}
+
+/// Which release is currently copied into `game_dir`, and the sha256 of each
+/// DLL as placed. `active_version` reads the tag; the hashes are recorded
+/// only, and neither `is_installed` nor anything else here compares them
+/// against the files in the game directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DxvkManifest {
+    active_tag: Option<String>,
+    dll_hashes: HashMap<String, String>,
+}
+
+fn load_manifest(mod_dir: &Path) -> Result<DxvkManifest> {
+    let path = manifest_path(mod_dir);
+    if !path.exists() {
+        return Ok(DxvkManifest::default());
+    }
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read manifest from {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| "Failed to parse DXVK manifest JSON")
+}
+
+fn save_manifest(mod_dir: &Path, manifest: &DxvkManifest) -> Result<()> {
+    fs::create_dir_all(cache_dir(mod_dir))?;
+    let data = serde_json::to_vec_pretty(manifest).with_context(|| "Failed to serialize DXVK manifest")?;
+    fs::write(manifest_path(mod_dir), data)
+        .with_context(|| format!("Failed to write manifest to {}", manifest_path(mod_dir).display()))
+}
+
+/// The tag of the release currently copied into the game directory, as
+/// recorded by the last `activate` call, if any.
+pub fn active_version(mod_dir: &Path) -> Option<String> {
+    load_manifest(mod_dir).ok()?.active_tag
+}
+
+/// Scans the cache for releases whose DLLs have already been downloaded and
+/// extracted, so the UI can offer instant switching without a re-download.
+pub fn list_installed(mod_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(cache_dir(mod_dir)) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter(|entry| DXVK_ASYNC_FILES.iter().all(|f| entry.path().join(f).exists()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct ReleaseAssetLink {
     url: String,
@@ -59,6 +151,41 @@ struct Release {
     tag_name: Option<String>,
 }
 
+/// A single selectable DXVK-GPLAsync release, as surfaced to the UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DxvkVersion {
+    pub tag: String,
+    pub display_name: String,
+    pub asset_url: String,
+}
+
+impl std::fmt::Display for DxvkVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
+
+/// Queries the upstream release feed and returns the available DXVK-GPLAsync
+/// builds, newest first, for the version dropdown in `dxvk_section`.
+pub fn list_available() -> Result<Vec<DxvkVersion>> {
+    let releases: Vec<Release> = reqwest::blocking::get(DXVK_RELEASE_API)?.json()?;
+    releases
+        .iter()
+        .map(|release| {
+            let tag = release
+                .tag_name
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("DXVK release is missing a tag_name"))?;
+            let asset_url = preferred_asset_url(release)?;
+            Ok(DxvkVersion {
+                display_name: release.name.clone().unwrap_or_else(|| tag.clone()),
+                tag,
+                asset_url,
+            })
+        })
+        .collect()
+}
+
 fn preferred_asset_url(release: &Release) -> Result<String> {
     let mut candidates: Vec<&ReleaseAssetLink> = Vec::new();
     if let Some(ref assets) = release.assets {
@@ -93,45 +220,64 @@ fn preferred_asset_url(release: &Release) -> Result<String> {
     Ok(candidates[0].url.clone())
 }
 
-pub fn install(game_dir: &Path, mod_dir: &Path) -> Result<()> {
-    if is_installed(game_dir) {
-        log(LogCategory::Info, "DXVK-GPLAsync is already installed");
-        return Ok(());
-    }
-
+/// Installs the newest available DXVK-GPLAsync release into the cache and
+/// activates it. Returns the tag of the version that was installed so
+/// callers can stamp it into `AppSettings`.
+pub fn install(
+    game_dir: &Path,
+    mod_dir: &Path,
+    progress: Option<&ProgressSender>,
+    cancel: Option<&AtomicBool>,
+) -> Result<String> {
     log(LogCategory::Info, "Querying DXVK-GPLAsync releases...");
-    let releases: Vec<Release> = reqwest::blocking::get(DXVK_RELEASE_API)?.json()?;
-    let release = releases
-        .get(0)
+    let releases = list_available()?;
+    let version = releases
+        .into_iter()
+        .next()
         .ok_or_else(|| anyhow::anyhow!("No releases returned from DXVK-GPLAsync API"))?;
-    let url = preferred_asset_url(release)?;
+    install_version(mod_dir, &version, progress, cancel)?;
+    activate(game_dir, mod_dir, &version)?;
+    Ok(version.tag)
+}
+
+/// Downloads and extracts a specific DXVK-GPLAsync release chosen from
+/// `list_available` into its own cache folder under `mod_dir`, without
+/// touching `game_dir`. Returns the cache directory holding the DLLs.
+/// A no-op if the release is already cached (see `list_installed`).
+pub fn install_version(
+    mod_dir: &Path,
+    version: &DxvkVersion,
+    progress: Option<&ProgressSender>,
+    cancel: Option<&AtomicBool>,
+) -> Result<PathBuf> {
+    let dest_dir = version_dir(mod_dir, &version.tag);
+    if DXVK_ASYNC_FILES.iter().all(|f| dest_dir.join(f).exists()) {
+        log(
+            LogCategory::Info,
+            format!("{} is already cached", version.display_name),
+        );
+        return Ok(dest_dir);
+    }
+
     log(
         LogCategory::Info,
-        format!(
-            "Latest DXVK-GPLAsync release: {}",
-            release
-                .name
-                .as_ref()
-                .or(release.tag_name.as_ref())
-                .map(String::as_str)
-                .unwrap_or("Unknown")
-        ),
+        format!("Caching DXVK-GPLAsync {}", version.display_name),
     );
 
     fs::create_dir_all(mod_dir)?;
-    let archive_path = download_file(mod_dir, &url)?;
+    let archive_path = download_file(mod_dir, &version.asset_url, progress, cancel)?;
     log(
         LogCategory::Success,
-        format!("Downloaded DXVK archive from {}", url),
+        format!("Downloaded DXVK archive from {}", version.asset_url),
     );
 
-    let extract_dir = mod_dir.join("dxvk_extracted");
+    let extract_dir = cache_dir(mod_dir).join("_extract");
     if extract_dir.exists() {
         fs::remove_dir_all(&extract_dir).ok();
     }
     fs::create_dir_all(&extract_dir)?;
 
-    extract_archive(&archive_path, &extract_dir)?;
+    extract_archive(&archive_path, &extract_dir, progress)?;
     log(LogCategory::Success, "Extracted DXVK archive");
 
     let mut x64_dir: Option<PathBuf> = None;
@@ -139,64 +285,104 @@ pub fn install(game_dir: &Path, mod_dir: &Path) -> Result<()> {
         .into_iter()
         .filter_map(Result::ok)
     {
-        if entry.file_type().is_file() {
-            let file_name = entry.file_name().to_string_lossy();
-            if DXVK_ASYNC_FILES.iter().all(|f| {
+        if entry.file_type().is_file()
+            && DXVK_ASYNC_FILES.iter().all(|f| {
                 entry
                     .path()
                     .parent()
                     .map(|p| p.join(f).exists())
                     .unwrap_or(false)
-            }) {
-                x64_dir = entry.path().parent().map(|p| p.to_path_buf());
-                break;
-            }
+            })
+        {
+            x64_dir = entry.path().parent().map(|p| p.to_path_buf());
+            break;
         }
     }
 
-    if x64_dir.is_none() {
-        anyhow::bail!("Required DXVK files (dxgi.dll, d3d11.dll) not found in extracted archive");
-    }
-    let x64_dir = x64_dir.unwrap();
+    let x64_dir = x64_dir
+        .ok_or_else(|| anyhow::anyhow!("Required DXVK files (dxgi.dll, d3d11.dll) not found in extracted archive"))?;
 
+    fs::create_dir_all(&dest_dir)?;
     for file in DXVK_ASYNC_FILES {
         let src = x64_dir.join(file);
-        let dst = game_dir.join(file);
-        if src.exists() {
-            fs::copy(&src, &dst)?;
-            log(LogCategory::Success, format!("Installed {}", file));
-        } else {
+        if !src.exists() {
             anyhow::bail!("DXVK archive missing required file: {}", file);
         }
+        fs::copy(&src, dest_dir.join(file))?;
+    }
+
+    fs::remove_dir_all(&extract_dir).ok();
+    fs::remove_file(&archive_path).ok();
+    log(
+        LogCategory::Success,
+        format!("Cached DXVK-GPLAsync {}", version.display_name),
+    );
+
+    Ok(dest_dir)
+}
+
+/// Copies the DLLs for an already-cached release (see `install_version`)
+/// into `game_dir` and records it as the active version in the manifest.
+pub fn activate(game_dir: &Path, mod_dir: &Path, version: &DxvkVersion) -> Result<()> {
+    let cached_dir = version_dir(mod_dir, &version.tag);
+    if !DXVK_ASYNC_FILES.iter().all(|f| cached_dir.join(f).exists()) {
+        anyhow::bail!(
+            "DXVK-GPLAsync {} is not cached; install it first",
+            version.display_name
+        );
+    }
+
+    let mut tx = InstallTransaction::new();
+    let mut dll_hashes = HashMap::new();
+    for file in DXVK_ASYNC_FILES {
+        let dst = game_dir.join(file);
+        tx.copy_file(&cached_dir.join(file), &dst)?;
+        dll_hashes.insert(file.to_string(), hash_file(&dst)?);
+        log(LogCategory::Success, format!("Activated {}", file));
     }
 
     let conf_path = game_dir.join("dxvk.conf");
-    let mut file = File::create(&conf_path)?;
-    writeln!(file, "dxvk.enableAsync=true")?;
-    writeln!(file, "dxvk.gplAsyncCache=true")?;
-    writeln!(file, "dxvk.useRawSsbo=true")?;
+    let conf_contents = "dxvk.enableAsync=true\ndxvk.gplAsyncCache=true\ndxvk.useRawSsbo=true\n";
+    tx.write_file(&conf_path, conf_contents.as_bytes())?;
     log(LogCategory::Success, "Wrote dxvk.conf");
+    tx.commit();
+
+    save_manifest(
+        mod_dir,
+        &DxvkManifest {
+            active_tag: Some(version.tag.clone()),
+            dll_hashes,
+        },
+    )?;
+    log(
+        LogCategory::Success,
+        format!("DXVK-GPLAsync {} is now active", version.display_name),
+    );
 
     Ok(())
 }
 
-fn download_file(dir: &Path, url: &str) -> Result<PathBuf> {
-    let mut response =
-        reqwest::blocking::get(url).with_context(|| format!("Failed to download {}", url))?;
-    let filename = response
-        .url()
-        .path_segments()
-        .and_then(|segments| segments.last())
-        .filter(|name| !name.is_empty())
-        .map(String::from)
-        .unwrap_or_else(|| "dxvk-gplasync".to_string());
-    let path = dir.join(filename);
-    let mut file = File::create(&path)?;
-    std::io::copy(&mut response, &mut file)?;
-    Ok(path)
+fn download_file(
+    dir: &Path,
+    url: &str,
+    progress: Option<&ProgressSender>,
+    cancel: Option<&AtomicBool>,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let dest = dir.join(download::derive_filename(url));
+    let reporter = ChannelProgress {
+        sender: progress,
+        label: "Downloading DXVK-GPLAsync",
+        cancel,
+    };
+    download::download_resumable(&dest, url, &reporter)
 }
 
-fn extract_archive(archive_path: &Path, extract_dir: &Path) -> Result<()> {
+fn extract_archive(
+    archive_path: &Path,
+    extract_dir: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
     let lower = archive_path
         .extension()
         .and_then(|ext| ext.to_str())
@@ -209,7 +395,7 @@ fn extract_archive(archive_path: &Path, extract_dir: &Path) -> Result<()> {
         .map(|name| name.to_ascii_lowercase().ends_with(".zip"))
         .unwrap_or(false)
     {
-        return extract_zip(archive_path, extract_dir);
+        return extract_zip(archive_path, extract_dir, progress);
     }
 
     if lower == "gz"
@@ -220,7 +406,7 @@ fn extract_archive(archive_path: &Path, extract_dir: &Path) -> Result<()> {
             .map(|name| name.to_ascii_lowercase().ends_with(".tar.gz"))
             .unwrap_or(false)
     {
-        return extract_tar_gz(archive_path, extract_dir);
+        return extract_tar_gz(archive_path, extract_dir, progress);
     }
 
     if archive_path
@@ -229,7 +415,7 @@ fn extract_archive(archive_path: &Path, extract_dir: &Path) -> Result<()> {
         .map(|name| name.to_ascii_lowercase().ends_with(".tar.xz"))
         .unwrap_or(false)
     {
-        return extract_tar_xz(archive_path, extract_dir);
+        return extract_tar_xz(archive_path, extract_dir, progress);
     }
 
     if archive_path
@@ -241,15 +427,20 @@ fn extract_archive(archive_path: &Path, extract_dir: &Path) -> Result<()> {
         })
         .unwrap_or(false)
     {
-        return extract_tar_zst(archive_path, extract_dir);
+        return extract_tar_zst(archive_path, extract_dir, progress);
     }
 
     anyhow::bail!("Unsupported archive format: {}", archive_path.display())
 }
 
-fn extract_zip(archive_path: &Path, extract_dir: &Path) -> Result<()> {
+fn extract_zip(
+    archive_path: &Path,
+    extract_dir: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
     let file = File::open(archive_path)?;
     let mut archive = zip::ZipArchive::new(file)?;
+    let total = archive.len() as u64;
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
         let outpath = extract_dir.join(file.sanitized_name());
@@ -262,30 +453,52 @@ fn extract_zip(archive_path: &Path, extract_dir: &Path) -> Result<()> {
             let mut outfile = File::create(&outpath)?;
             std::io::copy(&mut file, &mut outfile)?;
         }
+        progress::report(progress, "Extracting DXVK archive", i as u64 + 1, total);
     }
     Ok(())
 }
 
-fn extract_tar_gz(archive_path: &Path, extract_dir: &Path) -> Result<()> {
+fn extract_tar_gz(
+    archive_path: &Path,
+    extract_dir: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
     let tar_gz = File::open(archive_path)?;
     let tar = GzDecoder::new(tar_gz);
-    let mut archive = Archive::new(tar);
-    archive.unpack(extract_dir)?;
-    Ok(())
+    unpack_tar_entries(Archive::new(tar), extract_dir, progress)
 }
 
-fn extract_tar_xz(archive_path: &Path, extract_dir: &Path) -> Result<()> {
+fn extract_tar_xz(
+    archive_path: &Path,
+    extract_dir: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
     let file = File::open(archive_path)?;
-    let mut decompressor = xz2::read::XzDecoder::new(file);
-    let mut archive = Archive::new(&mut decompressor);
-    archive.unpack(extract_dir)?;
-    Ok(())
+    let decompressor = xz2::read::XzDecoder::new(file);
+    unpack_tar_entries(Archive::new(decompressor), extract_dir, progress)
 }
 
-fn extract_tar_zst(archive_path: &Path, extract_dir: &Path) -> Result<()> {
+fn extract_tar_zst(
+    archive_path: &Path,
+    extract_dir: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
     let file = File::open(archive_path)?;
     let decoder = zstd::stream::read::Decoder::new(file)?;
-    let mut archive = Archive::new(decoder);
-    archive.unpack(extract_dir)?;
+    unpack_tar_entries(Archive::new(decoder), extract_dir, progress)
+}
+
+fn unpack_tar_entries<R: Read>(
+    mut archive: Archive<R>,
+    extract_dir: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
+    let mut extracted = 0u64;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        entry.unpack_in(extract_dir)?;
+        extracted += 1;
+        progress::report(progress, "Extracting DXVK archive", extracted, 0);
+    }
     Ok(())
 }
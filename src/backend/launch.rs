@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command as OsCommand, ExitStatus};
+
+use anyhow::{Context, Result};
+
+use crate::backend::dxvk;
+use crate::logging::{LogCategory, log};
+
+const GAME_EXECUTABLE: &str = "BlackOps3.exe";
+const BUBBLEWRAP_BIN: &str = "bwrap";
+
+/// Creates and initializes `prefix` with `runner` if it doesn't exist yet,
+/// mirroring the prefix-bootstrap flow of the Wine/Proton launchers: a fresh
+/// prefix is created the first time `wineboot` runs against it.
+pub fn ensure_prefix(runner: &Path, prefix: &Path) -> Result<()> {
+    if prefix.exists() {
+        log(LogCategory::Info, "Wine prefix already initialized");
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(prefix)
+        .with_context(|| format!("Failed to create prefix directory {}", prefix.display()))?;
+
+    log(LogCategory::Info, "Initializing Wine prefix...");
+    let status = OsCommand::new(runner)
+        .arg("wineboot")
+        .arg("--init")
+        .env("WINEPREFIX", prefix)
+        .status()
+        .with_context(|| format!("Failed to run {} wineboot --init", runner.display()))?;
+
+    if !status.success() {
+        anyhow::bail!("wineboot --init exited with {}", status);
+    }
+    log(LogCategory::Success, "Wine prefix initialized");
+    Ok(())
+}
+
+/// Registers the DLL overrides DXVK needs (native before builtin) in
+/// `prefix`'s registry so the `dxgi.dll`/`d3d11.dll` placed by
+/// `backend::dxvk` take effect instead of Wine's built-in stubs.
+pub fn configure_dxvk_overrides(runner: &Path, prefix: &Path) -> Result<()> {
+    for dll in dxvk::override_dll_names() {
+        let status = OsCommand::new(runner)
+            .args(["reg", "add", "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides"])
+            .args(["/v", dll, "/d", "native,builtin", "/f"])
+            .env("WINEPREFIX", prefix)
+            .status()
+            .with_context(|| format!("Failed to set DLL override for {dll}"))?;
+        if !status.success() {
+            anyhow::bail!("Setting DLL override for {dll} exited with {}", status);
+        }
+    }
+    log(LogCategory::Success, "Configured DXVK DLL overrides");
+    Ok(())
+}
+
+/// Launches `BlackOps3.exe` from `game_dir` through `runner` inside
+/// `prefix`, creating and configuring the prefix first if necessary.
+/// When `sandboxed` is set, the game process runs under `bwrap` with only
+/// `game_dir`, `prefix`, and the display/audio sockets writable and the rest
+/// of `$HOME` hidden (see `launch_sandboxed` for the full set of binds).
+pub fn launch_game(
+    game_dir: &Path,
+    runner: &Path,
+    prefix: &Path,
+    sandboxed: bool,
+) -> Result<()> {
+    let executable = game_dir.join(GAME_EXECUTABLE);
+    if !executable.exists() {
+        anyhow::bail!("{} not found in {}", GAME_EXECUTABLE, game_dir.display());
+    }
+
+    ensure_prefix(runner, prefix)?;
+    configure_dxvk_overrides(runner, prefix)?;
+
+    log(LogCategory::Info, format!("Launching {}", GAME_EXECUTABLE));
+    let status = if sandboxed {
+        launch_sandboxed(&executable, runner, game_dir, prefix)?
+    } else {
+        OsCommand::new(runner)
+            .arg(&executable)
+            .current_dir(game_dir)
+            .env("WINEPREFIX", prefix)
+            .status()
+            .with_context(|| {
+                format!("Failed to launch {} via {}", executable.display(), runner.display())
+            })?
+    };
+
+    if !status.success() {
+        anyhow::bail!("Game process exited with {}", status);
+    }
+    log(LogCategory::Success, "Game session ended");
+    Ok(())
+}
+
+/// Finds the installation root of a Wine/Proton runner binary by walking up
+/// past the `bin`/`files`/`dist` leaf directories Proton-GE and Lutris
+/// builds nest their executable under, so the whole runner install (shared
+/// libraries, `files/share`, etc.) ends up inside one bind-mount instead of
+/// just the directory holding the binary itself.
+fn runner_install_root(runner: &Path) -> Option<PathBuf> {
+    let mut dir = runner.parent()?.to_path_buf();
+    while let Some(name) = dir.file_name().and_then(|n| n.to_str()) {
+        if matches!(name, "bin" | "files" | "dist") {
+            dir = dir.parent()?.to_path_buf();
+        } else {
+            break;
+        }
+    }
+    Some(dir)
+}
+
+/// Steam's `pressure-vessel` runtime (e.g. `SteamLinuxRuntime_sniper`), which
+/// Proton builds re-exec themselves through and which a Proton-GE runner
+/// depends on even though it lives outside the runner's own install
+/// directory. Absent for plain system Wine or Lutris runners.
+fn steam_runtime_dirs() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+
+    let mut dirs = Vec::new();
+    let legacy_runtime = home.join(".steam/steam/ubuntu12_32");
+    if legacy_runtime.exists() {
+        dirs.push(legacy_runtime);
+    }
+
+    let common = home.join(".steam/steam/steamapps/common");
+    if let Ok(entries) = std::fs::read_dir(&common) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("SteamLinuxRuntime") {
+                dirs.push(entry.path());
+            }
+        }
+    }
+    dirs
+}
+
+/// GPU device nodes under `/dev` the game needs to render anything: the DRM
+/// render/card nodes every Mesa (AMD/Intel) driver opens, plus the NVIDIA
+/// proprietary driver's `/dev/nvidia*` nodes when present. `--dev /dev`
+/// alone only creates a minimal devtmpfs (null/zero/random/tty), so without
+/// these explicit binds the sandboxed process has no device to render with.
+fn gpu_device_binds() -> Vec<PathBuf> {
+    let mut binds = Vec::new();
+    let dri = PathBuf::from("/dev/dri");
+    if dri.exists() {
+        binds.push(dri);
+    }
+    if let Ok(entries) = std::fs::read_dir("/dev") {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("nvidia") {
+                binds.push(entry.path());
+            }
+        }
+    }
+    binds
+}
+
+/// The display/compositor sockets Wine needs to put a window on screen:
+/// the X11 socket directory and (for Wayland, or X11 running under a
+/// Wayland/Pipewire session) `XDG_RUNTIME_DIR`, which also carries the
+/// PulseAudio/PipeWire sockets for audio. Bound read-write since clients
+/// create their own socket files inside `XDG_RUNTIME_DIR`.
+fn display_server_binds() -> Vec<PathBuf> {
+    let mut binds = Vec::new();
+    let x11_socket_dir = PathBuf::from("/tmp/.X11-unix");
+    if x11_socket_dir.exists() {
+        binds.push(x11_socket_dir);
+    }
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        let runtime_dir = PathBuf::from(runtime_dir);
+        if runtime_dir.exists() {
+            binds.push(runtime_dir);
+        }
+    }
+    binds
+}
+
+/// Runs the game through `bwrap`, binding `game_dir` and `prefix` read-write,
+/// the runner's own install directory and any Steam runtime it depends on
+/// read-only, the GPU device nodes and display/audio sockets the game needs
+/// to actually render and show a window, and the handful of system paths
+/// Wine needs, with the rest of `$HOME` hidden behind a tmpfs so untrusted
+/// lobby/mod content can't reach it.
+fn launch_sandboxed(
+    executable: &Path,
+    runner: &Path,
+    game_dir: &Path,
+    prefix: &Path,
+) -> Result<ExitStatus> {
+    log(LogCategory::Info, "Launching inside a bubblewrap sandbox");
+
+    let mut command = OsCommand::new(BUBBLEWRAP_BIN);
+    command
+        .arg("--die-with-parent")
+        .args(["--ro-bind", "/usr", "/usr"])
+        .args(["--ro-bind", "/etc", "/etc"])
+        .args(["--symlink", "usr/lib", "/lib"])
+        .args(["--symlink", "usr/lib64", "/lib64"])
+        .args(["--symlink", "usr/bin", "/bin"])
+        .args(["--proc", "/proc"])
+        .args(["--dev", "/dev"]);
+
+    for dir in gpu_device_binds() {
+        command.args(["--dev-bind", &dir.to_string_lossy(), &dir.to_string_lossy()]);
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        command.args(["--tmpfs", &home.to_string_lossy()]);
+    }
+    command
+        .args(["--bind", &game_dir.to_string_lossy(), &game_dir.to_string_lossy()])
+        .args(["--bind", &prefix.to_string_lossy(), &prefix.to_string_lossy()]);
+
+    for dir in display_server_binds() {
+        command.args(["--bind", &dir.to_string_lossy(), &dir.to_string_lossy()]);
+    }
+
+    let mut ro_binds: Vec<PathBuf> = runner_install_root(runner).into_iter().collect();
+    ro_binds.extend(steam_runtime_dirs());
+    for dir in ro_binds {
+        if !dir.exists() || dir.starts_with("/usr") || dir.starts_with("/etc") {
+            continue;
+        }
+        command.args(["--ro-bind", &dir.to_string_lossy(), &dir.to_string_lossy()]);
+    }
+
+    command
+        .arg(runner)
+        .arg(executable)
+        .current_dir(game_dir)
+        .env("WINEPREFIX", prefix)
+        .status()
+        .with_context(|| format!("Failed to launch {} under bwrap", executable.display()))
+}
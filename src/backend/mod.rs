@@ -0,0 +1,11 @@
+pub mod colored_name;
+pub mod config;
+pub mod download;
+pub mod dxvk;
+pub mod launch;
+pub mod progress;
+pub mod state;
+pub mod t7patch;
+pub mod transaction;
+pub mod updates;
+pub mod verify;
@@ -0,0 +1,23 @@
+/// A single progress sample emitted by a long-running backend operation
+/// (download or archive extraction). `total` is `0` when the size isn't
+/// known ahead of time (e.g. a streaming tar decode).
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub label: String,
+    pub current: u64,
+    pub total: u64,
+}
+
+/// The channel a backend operation reports `Progress` samples on. Callers
+/// that don't care about progress (tests, headless use) can pass `None`.
+pub type ProgressSender = flume::Sender<Progress>;
+
+pub fn report(sender: Option<&ProgressSender>, label: &str, current: u64, total: u64) {
+    if let Some(sender) = sender {
+        let _ = sender.send(Progress {
+            label: label.to_string(),
+            current,
+            total,
+        });
+    }
+}
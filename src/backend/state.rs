@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::Path;
+
+use crate::backend::{dxvk, t7patch};
+
+/// Status of a single managed component, as surfaced by `installation_state`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentState {
+    NotInstalled,
+    Installed { version: String },
+    UpdateAvailable { current: String, latest: String },
+    FilesMissing(Vec<String>),
+}
+
+/// The overall state of a game directory across every component this app
+/// manages, so a front-end can drive its enable/disable/repair buttons from
+/// a single call instead of re-deriving it from scattered file checks.
+#[derive(Debug, Clone)]
+pub struct InstallState {
+    pub t7_patch: ComponentState,
+    pub dxvk: ComponentState,
+    /// `.ff.bak` LPC backups left behind with no corresponding live `.ff`,
+    /// e.g. after a manual file deletion; `restore_lpc_backups` would rename
+    /// each of these back to its live `.ff` name.
+    pub dangling_lpc_backups: Vec<String>,
+}
+
+/// Queries the on-disk state of every managed component. `latest_t7_version`
+/// and `latest_dxvk_version` are the results of a previous, already-completed
+/// `updates::check_for_updates` call (or `None` if one hasn't run yet) — this
+/// function never hits the network itself, so it's cheap enough to call
+/// synchronously from the GUI thread.
+pub fn installation_state(
+    game_dir: &Path,
+    mod_dir: &Path,
+    known_t7_version: Option<&str>,
+    latest_t7_version: Option<&str>,
+    latest_dxvk_version: Option<&str>,
+) -> InstallState {
+    InstallState {
+        t7_patch: t7_patch_state(game_dir, known_t7_version, latest_t7_version),
+        dxvk: dxvk_state(game_dir, mod_dir, latest_dxvk_version),
+        dangling_lpc_backups: find_dangling_lpc_backups(game_dir),
+    }
+}
+
+fn t7_patch_state(
+    game_dir: &Path,
+    known_version: Option<&str>,
+    latest_version: Option<&str>,
+) -> ComponentState {
+    const REQUIRED_FILES: [&str; 2] = ["t7patch.dll", "dsound.dll"];
+    let mut missing: Vec<String> = REQUIRED_FILES
+        .iter()
+        .filter(|file| !game_dir.join(file).exists())
+        .map(|file| file.to_string())
+        .collect();
+
+    if missing.len() == REQUIRED_FILES.len() {
+        return ComponentState::NotInstalled;
+    }
+
+    match t7patch::check_t7_patch_status(game_dir) {
+        Ok(status) if status.gamertag.is_some() => {}
+        _ => missing.push("t7patch.conf".to_string()),
+    }
+
+    if !missing.is_empty() {
+        return ComponentState::FilesMissing(missing);
+    }
+
+    let current = known_version.unwrap_or_default().to_string();
+    match latest_version {
+        Some(latest) if !current.is_empty() && latest != current => ComponentState::UpdateAvailable {
+            current,
+            latest: latest.to_string(),
+        },
+        _ => ComponentState::Installed { version: current },
+    }
+}
+
+fn dxvk_state(game_dir: &Path, mod_dir: &Path, latest_version: Option<&str>) -> ComponentState {
+    let missing = dxvk::missing_files(game_dir);
+    let total_files = dxvk::override_dll_names().count();
+
+    if missing.len() == total_files {
+        return ComponentState::NotInstalled;
+    }
+    if !missing.is_empty() {
+        return ComponentState::FilesMissing(missing);
+    }
+
+    let Some(current) = dxvk::active_version(mod_dir) else {
+        return ComponentState::Installed {
+            version: "unknown".to_string(),
+        };
+    };
+
+    match latest_version {
+        Some(latest) if latest != current => ComponentState::UpdateAvailable {
+            current,
+            latest: latest.to_string(),
+        },
+        _ => ComponentState::Installed { version: current },
+    }
+}
+
+fn find_dangling_lpc_backups(game_dir: &Path) -> Vec<String> {
+    let lpc_dir = game_dir.join("LPC");
+    let Ok(entries) = fs::read_dir(&lpc_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let live_name = file_name
+                .strip_suffix(".bak")
+                .filter(|name| name.ends_with(".ff"))?;
+            if lpc_dir.join(live_name).exists() {
+                None
+            } else {
+                Some(file_name)
+            }
+        })
+        .collect()
+}
@@ -1,10 +1,16 @@
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 
 use anyhow::{Context, Result};
 use walkdir::WalkDir;
 
+use crate::backend::colored_name::ColoredName;
+use crate::backend::download::{self, ChannelProgress};
+use crate::backend::progress::{self, ProgressSender};
+use crate::backend::transaction::InstallTransaction;
+use crate::backend::updates;
 use crate::logging::{LogCategory, log};
 
 const T7PATCH_URL: &str = "https://github.com/shiversoftdev/t7patch/releases/download/Current/Linux.Steamdeck.and.Manual.Windows.Install.zip";
@@ -13,7 +19,7 @@ const LPC_URL: &str =
 
 pub fn update_t7patch_conf(
     game_dir: &Path,
-    new_name: Option<&str>,
+    new_name: Option<&ColoredName>,
     new_password: Option<&str>,
     friends_only: Option<bool>,
 ) -> Result<()> {
@@ -38,7 +44,7 @@ pub fn update_t7patch_conf(
         let line = line?;
         if let Some(name) = new_name {
             if line.starts_with("playername=") {
-                lines.push(format!("playername={}", name));
+                lines.push(format!("playername={}", name.to_raw()));
                 name_found = true;
                 continue;
             }
@@ -62,7 +68,7 @@ pub fn update_t7patch_conf(
 
     if let Some(name) = new_name {
         if !name_found {
-            lines.push(format!("playername={}", name));
+            lines.push(format!("playername={}", name.to_raw()));
         }
         log(
             LogCategory::Success,
@@ -125,12 +131,7 @@ pub fn check_t7_patch_status(game_dir: &Path) -> Result<T7PatchStatus> {
     }
 
     if let Some(ref tag) = status.gamertag {
-        if tag.starts_with('^') && tag.len() >= 2 {
-            status.color_code = Some(tag[..2].to_string());
-            status.plain_name = Some(tag[2..].to_string());
-        } else {
-            status.plain_name = Some(tag.clone());
-        }
+        status.parsed_name = Some(ColoredName::parse(tag));
     }
 
     Ok(status)
@@ -139,23 +140,41 @@ pub fn check_t7_patch_status(game_dir: &Path) -> Result<T7PatchStatus> {
 #[derive(Debug, Default, Clone)]
 pub struct T7PatchStatus {
     pub gamertag: Option<String>,
-    pub plain_name: Option<String>,
-    pub color_code: Option<String>,
+    /// The full tokenized form of `gamertag`, covering every color segment
+    /// rather than just a single leading `^N` prefix.
+    pub parsed_name: Option<ColoredName>,
     pub password: Option<String>,
     pub friends_only: Option<bool>,
 }
 
-pub fn install_t7_patch(game_dir: &Path, mod_dir: &Path) -> Result<()> {
+/// Downloads and installs the T7 patch, returning the installed version
+/// string (the upstream release's name, or its publish timestamp when the
+/// name is empty) so callers can stamp
+/// it into `AppSettings` for `updates::check_for_updates` to compare against.
+pub fn install_t7_patch(
+    game_dir: &Path,
+    mod_dir: &Path,
+    progress: Option<&ProgressSender>,
+    cancel: Option<&AtomicBool>,
+) -> Result<String> {
     log(LogCategory::Info, "Downloading T7 Patch...");
-    let archive = download_to(mod_dir, T7PATCH_URL, "T7Patch.zip")?;
+    let archive = download_to(
+        mod_dir,
+        T7PATCH_URL,
+        "T7Patch.zip",
+        "Downloading T7 Patch",
+        progress,
+        cancel,
+    )?;
     let extract_dir = mod_dir.join("linux");
     if extract_dir.exists() {
         fs::remove_dir_all(&extract_dir).ok();
     }
-    unzip(&archive, mod_dir)?;
+    unzip(&archive, mod_dir, "Extracting T7 Patch", progress)?;
     log(LogCategory::Success, "Extracted T7 Patch archive");
 
     if extract_dir.exists() {
+        let mut tx = InstallTransaction::new();
         for entry in WalkDir::new(&extract_dir)
             .into_iter()
             .filter_map(Result::ok)
@@ -175,19 +194,17 @@ pub fn install_t7_patch(game_dir: &Path, mod_dir: &Path) -> Result<()> {
                 {
                     continue;
                 }
-                if let Some(parent) = destination.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                fs::copy(entry.path(), &destination)?;
+                tx.copy_file(entry.path(), &destination)?;
             }
         }
+        tx.commit();
     } else {
         anyhow::bail!("Extracted archive did not contain linux/ directory");
     }
 
-    install_lpc_files(game_dir, mod_dir)?;
+    install_lpc_files(game_dir, mod_dir, progress, cancel)?;
     log(LogCategory::Success, "T7 Patch installation complete");
-    Ok(())
+    Ok(updates::latest_t7_patch_version().unwrap_or_default())
 }
 
 pub fn uninstall_t7_patch(game_dir: &Path, mod_dir: &Path) -> Result<()> {
@@ -229,13 +246,25 @@ pub fn uninstall_t7_patch(game_dir: &Path, mod_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn install_lpc_files(game_dir: &Path, mod_dir: &Path) -> Result<()> {
-    let archive = download_to(mod_dir, LPC_URL, "LPC.zip")?;
+pub fn install_lpc_files(
+    game_dir: &Path,
+    mod_dir: &Path,
+    progress: Option<&ProgressSender>,
+    cancel: Option<&AtomicBool>,
+) -> Result<()> {
+    let archive = download_to(
+        mod_dir,
+        LPC_URL,
+        "LPC.zip",
+        "Downloading LPC files",
+        progress,
+        cancel,
+    )?;
     let temp_dir = mod_dir.join("LPC_temp");
     if temp_dir.exists() {
         fs::remove_dir_all(&temp_dir).ok();
     }
-    unzip(&archive, &temp_dir)?;
+    unzip(&archive, &temp_dir, "Extracting LPC files", progress)?;
 
     let lpc_dir = game_dir.join("LPC");
     fs::create_dir_all(&lpc_dir)?;
@@ -248,18 +277,35 @@ pub fn install_lpc_files(game_dir: &Path, mod_dir: &Path) -> Result<()> {
         temp_dir.clone()
     };
 
-    for entry in WalkDir::new(&src_lpc).into_iter().filter_map(Result::ok) {
+    if let Err(err) = copy_lpc_files(&src_lpc, &lpc_dir) {
+        // `backup_lpc_files` already moved the live `.ff` files aside, so a
+        // failed copy can't just roll back the new files it wrote partway
+        // through — it has to bring the originals back too.
+        restore_lpc_backups(game_dir).ok();
+        return Err(err);
+    }
+
+    fs::remove_file(&archive).ok();
+    fs::remove_dir_all(&temp_dir).ok();
+    log(LogCategory::Success, "Installed LPC files successfully");
+    Ok(())
+}
+
+/// Copies every `.ff` file from `src_lpc` into `lpc_dir` under an
+/// `InstallTransaction`, so a failure partway through (e.g. disk full) rolls
+/// back the files it had already written instead of leaving `lpc_dir` with a
+/// mix of new and missing `.ff` files.
+fn copy_lpc_files(src_lpc: &Path, lpc_dir: &Path) -> Result<()> {
+    let mut tx = InstallTransaction::new();
+    for entry in WalkDir::new(src_lpc).into_iter().filter_map(Result::ok) {
         if entry.file_type().is_file()
             && entry.path().extension().and_then(|s| s.to_str()) == Some("ff")
         {
             let dest = lpc_dir.join(entry.file_name());
-            fs::copy(entry.path(), dest)?;
+            tx.copy_file(entry.path(), &dest)?;
         }
     }
-
-    fs::remove_file(&archive).ok();
-    fs::remove_dir_all(&temp_dir).ok();
-    log(LogCategory::Success, "Installed LPC files successfully");
+    tx.commit();
     Ok(())
 }
 
@@ -295,38 +341,51 @@ pub fn restore_lpc_backups(game_dir: &Path) -> Result<()> {
     for entry in fs::read_dir(&lpc_dir)? {
         let entry = entry?;
         let path = entry.path();
-        if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-            if ext == "bak" {
-                let dest = path.with_extension("ff");
-                if dest.exists() {
-                    fs::remove_file(&dest)?;
-                }
-                fs::rename(&path, &dest)?;
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        // `.with_extension` only replaces the last extension component, which
+        // would turn `weapon.ff.bak` into `weapon.ff.ff` instead of restoring
+        // it to `weapon.ff` — strip the `.bak` suffix from the file name directly.
+        if let Some(live_name) = file_name.strip_suffix(".bak") {
+            let dest = lpc_dir.join(live_name);
+            if dest.exists() {
+                fs::remove_file(&dest)?;
             }
+            fs::rename(&path, &dest)?;
         }
     }
     log(LogCategory::Success, "Restored LPC backups");
     Ok(())
 }
 
-fn download_to(dir: &Path, url: &str, filename: &str) -> Result<PathBuf> {
+fn download_to(
+    dir: &Path,
+    url: &str,
+    filename: &str,
+    label: &str,
+    progress: Option<&ProgressSender>,
+    cancel: Option<&AtomicBool>,
+) -> Result<PathBuf> {
     fs::create_dir_all(dir)?;
-    let path = dir.join(filename);
-    let mut response =
-        reqwest::blocking::get(url).with_context(|| format!("Failed to download {}", url))?;
-    if !response.status().is_success() {
-        anyhow::bail!("Failed to download {}: {}", url, response.status());
-    }
-    let mut file = File::create(&path)?;
-    while let Some(chunk) = response.chunk().transpose()? {
-        file.write_all(&chunk)?;
-    }
-    Ok(path)
+    let dest = dir.join(filename);
+    let reporter = ChannelProgress {
+        sender: progress,
+        label,
+        cancel,
+    };
+    download::download_resumable(&dest, url, &reporter)
 }
 
-fn unzip(archive: &Path, destination: &Path) -> Result<()> {
+fn unzip(
+    archive: &Path,
+    destination: &Path,
+    label: &str,
+    progress: Option<&ProgressSender>,
+) -> Result<()> {
     let file = File::open(archive)?;
     let mut archive = zip::ZipArchive::new(file)?;
+    let total = archive.len() as u64;
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
         let outpath = destination.join(file.sanitized_name());
@@ -340,6 +399,7 @@ fn unzip(archive: &Path, destination: &Path) -> Result<()> {
             let mut outfile = File::create(&outpath)?;
             std::io::copy(&mut file, &mut outfile)?;
         }
+        progress::report(progress, label, i as u64 + 1, total);
     }
     Ok(())
 }
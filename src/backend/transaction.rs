@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::logging::{LogCategory, log};
+
+/// One filesystem mutation made under an `InstallTransaction`, recorded in
+/// the order it happened so `Drop` can undo them in reverse.
+enum Action {
+    CreatedFile(PathBuf),
+    OverwrittenFile { path: PathBuf, backup: PathBuf },
+    CreatedDir(PathBuf),
+}
+
+/// Tracks every file/directory an install step creates or overwrites so a
+/// failure partway through can be undone instead of leaving the game
+/// directory half-patched. Call `commit()` once the install succeeds;
+/// otherwise `Drop` rolls everything back automatically.
+#[derive(Default)]
+pub struct InstallTransaction {
+    actions: Vec<Action>,
+    committed: bool,
+}
+
+impl InstallTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates `path` and any missing parent directories, recording only the
+    /// directories that didn't already exist so rollback doesn't remove
+    /// something that was there before the transaction started.
+    pub fn create_dir_all(&mut self, path: &Path) -> Result<()> {
+        let mut missing = Vec::new();
+        let mut current = path;
+        while !current.exists() {
+            missing.push(current.to_path_buf());
+            match current.parent() {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        fs::create_dir_all(path)
+            .with_context(|| format!("Failed to create directory {}", path.display()))?;
+        for dir in missing.into_iter().rev() {
+            self.actions.push(Action::CreatedDir(dir));
+        }
+        Ok(())
+    }
+
+    /// Copies `src` to `dst`, stashing `dst`'s original bytes first if it
+    /// already exists so rollback can restore them.
+    pub fn copy_file(&mut self, src: &Path, dst: &Path) -> Result<()> {
+        if let Some(parent) = dst.parent() {
+            self.create_dir_all(parent)?;
+        }
+        self.stash(dst)?;
+        fs::copy(src, dst)
+            .with_context(|| format!("Failed to copy {} to {}", src.display(), dst.display()))?;
+        Ok(())
+    }
+
+    /// Writes `contents` to `path`, stashing any existing file first.
+    pub fn write_file(&mut self, path: &Path, contents: &[u8]) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+        self.stash(path)?;
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    fn stash(&mut self, path: &Path) -> Result<()> {
+        if path.exists() {
+            let backup = backup_path_for(path);
+            fs::copy(path, &backup)
+                .with_context(|| format!("Failed to back up {}", path.display()))?;
+            self.actions.push(Action::OverwrittenFile {
+                path: path.to_path_buf(),
+                backup,
+            });
+        } else {
+            self.actions.push(Action::CreatedFile(path.to_path_buf()));
+        }
+        Ok(())
+    }
+
+    /// Keeps every change made so far and discards the backups taken for
+    /// overwritten files.
+    pub fn commit(mut self) {
+        self.committed = true;
+        for action in self.actions.drain(..) {
+            if let Action::OverwrittenFile { backup, .. } = action {
+                let _ = fs::remove_file(backup);
+            }
+        }
+    }
+}
+
+impl Drop for InstallTransaction {
+    fn drop(&mut self) {
+        if self.committed || self.actions.is_empty() {
+            return;
+        }
+        for action in self.actions.drain(..).rev() {
+            match action {
+                Action::CreatedFile(path) => {
+                    let _ = fs::remove_file(&path);
+                }
+                Action::OverwrittenFile { path, backup } => {
+                    let _ = fs::copy(&backup, &path);
+                    let _ = fs::remove_file(&backup);
+                }
+                Action::CreatedDir(path) => {
+                    let _ = fs::remove_dir(&path);
+                }
+            }
+        }
+        log(
+            LogCategory::Warning,
+            "Install failed partway through; rolled back changes to restore the previous state",
+        );
+    }
+}
+
+fn backup_path_for(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.insttx-bak", file_name))
+}
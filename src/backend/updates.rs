@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::backend::dxvk;
+
+const T7PATCH_RELEASE_API: &str =
+    "https://api.github.com/repos/shiversoftdev/t7patch/releases/tags/Current";
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    name: Option<String>,
+    tag_name: String,
+    published_at: String,
+}
+
+/// The latest-known vs. currently-installed version of one managed
+/// component, as surfaced by the update banner.
+#[derive(Debug, Clone)]
+pub struct ComponentUpdate {
+    pub name: String,
+    pub current: Option<String>,
+    pub latest: Option<String>,
+}
+
+impl ComponentUpdate {
+    pub fn update_available(&self) -> bool {
+        match (&self.current, &self.latest) {
+            (Some(current), Some(latest)) => current != latest,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateCheckResult {
+    pub t7_patch: ComponentUpdate,
+    pub dxvk: ComponentUpdate,
+}
+
+/// The T7 patch project republishes new builds under the same `Current`
+/// release tag, so the tag never changes between builds. The release's
+/// `name` is what we stamp into `AppSettings` as the installed "version",
+/// falling back to `published_at` when the name is empty.
+pub fn latest_t7_patch_version() -> Result<String> {
+    let release: GithubRelease = reqwest::blocking::Client::new()
+        .get(T7PATCH_RELEASE_API)
+        .header("User-Agent", "PatchOpsIII")
+        .send()
+        .with_context(|| "Failed to query T7 patch release info")?
+        .json()?;
+    Ok(release
+        .name
+        .filter(|name| !name.is_empty())
+        .unwrap_or(release.published_at))
+}
+
+pub fn latest_dxvk_version() -> Result<String> {
+    let versions = dxvk::list_available()?;
+    versions
+        .into_iter()
+        .next()
+        .map(|v| v.tag)
+        .ok_or_else(|| anyhow::anyhow!("No DXVK-GPLAsync releases available"))
+}
+
+/// Queries upstream for the latest T7 patch and DXVK-GPLAsync versions and
+/// compares them against what's currently stamped into `AppSettings`.
+pub fn check_for_updates(
+    current_t7_patch: Option<String>,
+    current_dxvk: Option<String>,
+) -> Result<UpdateCheckResult> {
+    let latest_t7_patch = latest_t7_patch_version().ok();
+    let latest_dxvk = latest_dxvk_version().ok();
+
+    Ok(UpdateCheckResult {
+        t7_patch: ComponentUpdate {
+            name: "T7 Patch".to_string(),
+            current: current_t7_patch,
+            latest: latest_t7_patch,
+        },
+        dxvk: ComponentUpdate {
+            name: "DXVK-GPLAsync".to_string(),
+            current: current_dxvk,
+            latest: latest_dxvk,
+        },
+    })
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::logging::{LogCategory, log};
+
+const MANIFEST_FILE: &str = "verify_manifest.json";
+
+/// Files a clean BO3 + T7 patch + DXVK-GPLAsync install is expected to
+/// have. Used as the
+/// existence-only fallback when no checksum manifest is available.
+const CRITICAL_FILES: &[&str] = &[
+    "BlackOps3.exe",
+    "t7patch.dll",
+    "t7patch.conf",
+    "t7patchloader.dll",
+    "dsound.dll",
+    "discord_game_sdk.dll",
+    "zbr2.dll",
+    "dxgi.dll",
+    "d3d11.dll",
+];
+
+/// A map of game-directory-relative path to the expected sha256 hash of a
+/// clean file, as produced (or downloaded) alongside the installer.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VerifyManifest(pub HashMap<String, String>);
+
+/// Loads a checksum manifest from disk. Returns an empty manifest (which
+/// downgrades verification to existence-only checks) if none is present.
+pub fn load_manifest(path: &Path) -> Result<VerifyManifest> {
+    if !path.exists() {
+        return Ok(VerifyManifest::default());
+    }
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read manifest from {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| "Failed to parse verify manifest JSON")
+}
+
+pub fn default_manifest_path(mod_dir: &Path) -> PathBuf {
+    mod_dir.join(MANIFEST_FILE)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    /// Present and matches the manifest's known-good hash.
+    Ok,
+    /// Present, but no hash was available to verify it against.
+    Unverified,
+    /// Expected by the manifest or the critical-file list, but not on disk.
+    Missing,
+    /// Present, but its hash doesn't match the manifest.
+    Modified,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileCheck {
+    pub relative_path: String,
+    pub status: FileStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub entries: Vec<FileCheck>,
+}
+
+impl VerifyReport {
+    pub fn is_clean(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| matches!(entry.status, FileStatus::Ok | FileStatus::Unverified))
+    }
+
+    pub fn failing(&self) -> impl Iterator<Item = &FileCheck> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.status, FileStatus::Missing | FileStatus::Modified))
+    }
+}
+
+/// Checks each file named in `manifest` under `game_dir` against its
+/// expected hash, reporting it as `Ok`, `Missing`, or `Modified` (a corrupt
+/// file shows up as `Modified`). If the manifest is empty, each of
+/// `CRITICAL_FILES` is checked for existence only (`Unverified`/`Missing`).
+/// Files not named in either list are not looked at.
+pub fn verify_game(game_dir: &Path, manifest: &VerifyManifest) -> Result<VerifyReport> {
+    let mut entries = Vec::new();
+
+    if manifest.0.is_empty() {
+        for relative_path in CRITICAL_FILES {
+            let status = if game_dir.join(relative_path).exists() {
+                FileStatus::Unverified
+            } else {
+                FileStatus::Missing
+            };
+            entries.push(FileCheck {
+                relative_path: relative_path.to_string(),
+                status,
+            });
+        }
+    } else {
+        for (relative_path, expected_hash) in &manifest.0 {
+            let path = game_dir.join(relative_path);
+            let status = if !path.exists() {
+                FileStatus::Missing
+            } else {
+                let actual_hash = hash_file(&path)?;
+                if &actual_hash == expected_hash {
+                    FileStatus::Ok
+                } else {
+                    FileStatus::Modified
+                }
+            };
+            entries.push(FileCheck {
+                relative_path: relative_path.clone(),
+                status,
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    for entry in &entries {
+        match entry.status {
+            FileStatus::Ok | FileStatus::Unverified => {}
+            FileStatus::Missing => log(
+                LogCategory::Warning,
+                format!("Missing file: {}", entry.relative_path),
+            ),
+            FileStatus::Modified => log(
+                LogCategory::Warning,
+                format!("Modified file: {}", entry.relative_path),
+            ),
+        }
+    }
+    log(
+        LogCategory::Info,
+        format!("Verified {} tracked files", entries.len()),
+    );
+
+    Ok(VerifyReport { entries })
+}
+
+/// Re-copies any failing entries by searching the files under `mod_dir`
+/// (extracted patch files and cached DLLs; archives are not opened) for one
+/// of the same name and replacing the one in `game_dir`.
+pub fn repair(game_dir: &Path, mod_dir: &Path, report: &VerifyReport) -> Result<()> {
+    for entry in report.failing() {
+        let file_name = Path::new(&entry.relative_path)
+            .file_name()
+            .map(|n| n.to_os_string());
+        let Some(file_name) = file_name else {
+            continue;
+        };
+
+        let found = WalkDir::new(mod_dir)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .find(|candidate| candidate.file_type().is_file() && candidate.file_name() == file_name);
+
+        match found {
+            Some(candidate) => {
+                let dest = game_dir.join(&entry.relative_path);
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(candidate.path(), &dest)
+                    .with_context(|| format!("Failed to repair {}", entry.relative_path))?;
+                log(
+                    LogCategory::Success,
+                    format!("Repaired {} from {}", entry.relative_path, candidate.path().display()),
+                );
+            }
+            None => log(
+                LogCategory::Error,
+                format!(
+                    "Could not repair {}: no cached copy found in {}",
+                    entry.relative_path,
+                    mod_dir.display()
+                ),
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn hash_file(path: &Path) -> Result<String> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -1,12 +1,15 @@
-use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
 
-use chrono::Local;
+use chrono::{Local, NaiveDateTime};
 use flume::Sender;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use parking_lot::Mutex;
+use regex::Regex;
 
 #[derive(Clone, Debug)]
 pub struct Logger {
@@ -16,17 +19,56 @@ pub struct Logger {
 #[derive(Debug)]
 struct LoggerInner {
     file_path: PathBuf,
-    file: Mutex<File>,
+    file: Mutex<FileState>,
+    rolling: Option<RollingConfig>,
+}
+
+/// The open log file plus how many bytes have been written to it, tracked
+/// here (rather than re-`stat`ing the file) so every log line costs one
+/// write instead of a write plus a `metadata()` syscall.
+#[derive(Debug)]
+struct FileState {
+    file: File,
+    bytes_written: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RollingConfig {
+    max_bytes: u64,
+    max_archives: usize,
 }
 
 impl Logger {
     pub fn initialize(path: impl AsRef<Path>) -> std::io::Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let state = open_file_state(&path)?;
+        Ok(Self {
+            inner: Arc::new(LoggerInner {
+                file_path: path,
+                file: Mutex::new(state),
+                rolling: None,
+            }),
+        })
+    }
+
+    /// Like `initialize`, but once the live file would exceed `max_bytes`,
+    /// rotates `<file name>.{n}` archives (oldest at `max_archives`) before
+    /// continuing to log, instead of growing the file forever.
+    pub fn initialize_rolling(
+        path: impl AsRef<Path>,
+        max_bytes: u64,
+        max_archives: usize,
+    ) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let state = open_file_state(&path)?;
         Ok(Self {
             inner: Arc::new(LoggerInner {
                 file_path: path,
-                file: Mutex::new(file),
+                file: Mutex::new(state),
+                rolling: Some(RollingConfig {
+                    max_bytes,
+                    max_archives,
+                }),
             }),
         })
     }
@@ -40,20 +82,27 @@ impl Logger {
             category.as_str(),
             message.as_ref()
         );
-        if let Ok(mut handle) = self
+        if let Some(mut state) = self
             .inner
             .file
-            .lock()
             .try_lock_for(std::time::Duration::from_secs(1))
         {
-            let _ = handle.write_all(line.as_bytes());
+            if let Some(config) = &self.inner.rolling {
+                rotate_if_needed(&mut state, &self.inner.file_path, config, line.len() as u64);
+            }
+            if state.file.write_all(line.as_bytes()).is_ok() {
+                state.bytes_written += line.len() as u64;
+            }
         }
+        let entry = LogEntry {
+            timestamp: formatted.to_string(),
+            category,
+            message: message.as_ref().to_string(),
+        };
+        push_ring_entry(entry.clone());
+        mirror_to_console(category, &formatted.to_string(), message.as_ref());
         if let Some(sender) = LOG_CHANNEL.get() {
-            let _ = sender.send(LogEntry {
-                timestamp: formatted.to_string(),
-                category,
-                message: message.as_ref().to_string(),
-            });
+            let _ = sender.send(entry);
         }
     }
 
@@ -62,6 +111,56 @@ impl Logger {
     }
 }
 
+fn open_file_state(path: &Path) -> std::io::Result<FileState> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let bytes_written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    Ok(FileState {
+        file,
+        bytes_written,
+    })
+}
+
+fn archive_path(file_path: &Path, generation: usize) -> PathBuf {
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    file_path.with_file_name(format!("{}.{}", file_name, generation))
+}
+
+/// Holding `state`'s lock for the whole cascade is what keeps concurrent
+/// writers from interleaving a line into a file mid-rotation. A rename that
+/// fails logs nothing (that would recurse back into `log`) and simply
+/// leaves the oversized file in place rather than risk losing lines.
+fn rotate_if_needed(
+    state: &mut FileState,
+    file_path: &Path,
+    config: &RollingConfig,
+    incoming_line_len: u64,
+) {
+    if config.max_archives == 0 || state.bytes_written + incoming_line_len <= config.max_bytes {
+        return;
+    }
+
+    for generation in (1..config.max_archives).rev() {
+        let src = archive_path(file_path, generation);
+        if src.exists() {
+            let _ = fs::rename(&src, archive_path(file_path, generation + 1));
+        }
+    }
+
+    if fs::rename(file_path, archive_path(file_path, 1)).is_err() {
+        return;
+    }
+
+    let mut generation = config.max_archives + 1;
+    while archive_path(file_path, generation).exists() {
+        let _ = fs::remove_file(archive_path(file_path, generation));
+        generation += 1;
+    }
+
+    if let Ok(fresh) = open_file_state(file_path) {
+        *state = fresh;
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum LogCategory {
     Info,
@@ -79,6 +178,63 @@ impl LogCategory {
             LogCategory::Error => "Error",
         }
     }
+
+    /// Ranks categories for `RecordFilter::min_severity`. `Success` sits
+    /// alongside `Info` as routine, non-alarming output.
+    pub fn severity(self) -> u8 {
+        match self {
+            LogCategory::Info | LogCategory::Success => 0,
+            LogCategory::Warning => 1,
+            LogCategory::Error => 2,
+        }
+    }
+
+    /// Maps a `log` crate level onto our categories, for records produced by
+    /// `info!`/`warn!`-style macros in dependency crates. `Debug` and
+    /// `Trace` both collapse to `Info` since we don't distinguish them.
+    pub fn from_level(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LogCategory::Error,
+            log::Level::Warn => LogCategory::Warning,
+            log::Level::Info | log::Level::Debug | log::Level::Trace => LogCategory::Info,
+        }
+    }
+
+    pub fn to_level(self) -> log::Level {
+        match self {
+            LogCategory::Error => log::Level::Error,
+            LogCategory::Warning => log::Level::Warn,
+            LogCategory::Info | LogCategory::Success => log::Level::Info,
+        }
+    }
+
+    /// ANSI foreground escape for the console mirror: red for errors, yellow
+    /// for warnings, green for successes, and the default foreground for
+    /// routine `Info` lines.
+    fn ansi_escape(self) -> &'static str {
+        match self {
+            LogCategory::Error => "\x1b[31m",
+            LogCategory::Warning => "\x1b[33m",
+            LogCategory::Success => "\x1b[32m",
+            LogCategory::Info => "\x1b[39m",
+        }
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let category = LogCategory::from_level(record.level());
+        Logger::log(self, category, record.args().to_string());
+    }
+
+    fn flush(&self) {}
 }
 
 #[derive(Debug, Clone)]
@@ -88,11 +244,120 @@ pub struct LogEntry {
     pub message: String,
 }
 
+const DEFAULT_RING_CAPACITY: usize = 500;
+static RING_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_RING_CAPACITY);
+static RING_BUFFER: Lazy<Mutex<VecDeque<LogEntry>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+fn push_ring_entry(entry: LogEntry) {
+    let capacity = RING_CAPACITY.load(Ordering::Relaxed).max(1);
+    let mut buffer = RING_BUFFER.lock();
+    while buffer.len() >= capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(entry);
+}
+
+/// Resizes the in-memory log ring buffer, trimming the oldest entries
+/// immediately if it's shrinking.
+pub fn set_ring_buffer_capacity(capacity: usize) {
+    RING_CAPACITY.store(capacity.max(1), Ordering::Relaxed);
+    let mut buffer = RING_BUFFER.lock();
+    while buffer.len() > capacity.max(1) {
+        buffer.pop_front();
+    }
+}
+
+static CONSOLE_ENABLED: AtomicBool = AtomicBool::new(true);
+static CONSOLE_MIN_SEVERITY: AtomicU8 = AtomicU8::new(0);
+
+/// Turns off the stdout mirror entirely, e.g. for a GUI-only run where
+/// printing log lines to a console nobody sees would just waste cycles.
+pub fn disable_console() {
+    CONSOLE_ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Re-enables the stdout mirror after a prior `disable_console()` call.
+pub fn enable_console() {
+    CONSOLE_ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Suppresses console lines below `category`'s severity; the file and ring
+/// buffer still receive everything regardless of this setting.
+pub fn set_console_min_severity(category: LogCategory) {
+    CONSOLE_MIN_SEVERITY.store(category.severity(), Ordering::Relaxed);
+}
+
+/// Prints `message` to stdout with `category`'s ANSI color, unless the
+/// console mirror is disabled, `category` falls below the configured
+/// minimum severity, or stdout isn't a TTY (colors would just show up as
+/// raw escape codes in a redirected log).
+fn mirror_to_console(category: LogCategory, timestamp: &str, message: &str) {
+    if !CONSOLE_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if category.severity() < CONSOLE_MIN_SEVERITY.load(Ordering::Relaxed) {
+        return;
+    }
+    let line = format!("{} - {}: {}", timestamp, category.as_str(), message);
+    if std::io::stdout().is_terminal() {
+        println!("{}{}\x1b[0m", category.ansi_escape(), line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Criteria for `query`: every `Some` field must match for an entry to be
+/// included, and `limit` caps how many (newest-first) are returned.
+#[derive(Debug, Clone, Default)]
+pub struct RecordFilter {
+    pub min_severity: Option<LogCategory>,
+    pub message_matches: Option<Regex>,
+    pub not_before: Option<NaiveDateTime>,
+    pub limit: Option<usize>,
+}
+
+impl RecordFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if let Some(min_severity) = self.min_severity {
+            if entry.category.severity() < min_severity.severity() {
+                return false;
+            }
+        }
+        if let Some(regex) = &self.message_matches {
+            if !regex.is_match(&entry.message) {
+                return false;
+            }
+        }
+        if let Some(not_before) = self.not_before {
+            match NaiveDateTime::parse_from_str(&entry.timestamp, "%Y-%m-%d %H:%M:%S") {
+                Ok(timestamp) if timestamp >= not_before => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Walks the in-memory ring buffer newest-first, keeping entries that pass
+/// every predicate in `filter` and stopping once `filter.limit` is reached.
+pub fn query(filter: &RecordFilter) -> Vec<LogEntry> {
+    let limit = filter.limit.unwrap_or(usize::MAX);
+    RING_BUFFER
+        .lock()
+        .iter()
+        .rev()
+        .filter(|entry| filter.matches(entry))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
 static LOGGER: OnceCell<Logger> = OnceCell::new();
 static LOG_CHANNEL: OnceCell<Sender<LogEntry>> = OnceCell::new();
 
 pub fn init_global_logger(path: impl AsRef<Path>) -> std::io::Result<()> {
     let logger = Logger::initialize(path)?;
+    install_log_facade(&logger);
     LOGGER.set(logger).map_err(|_| {
         std::io::Error::new(
             std::io::ErrorKind::AlreadyExists,
@@ -101,6 +366,35 @@ pub fn init_global_logger(path: impl AsRef<Path>) -> std::io::Result<()> {
     })
 }
 
+pub fn init_global_logger_rolling(
+    path: impl AsRef<Path>,
+    max_bytes: u64,
+    max_archives: usize,
+) -> std::io::Result<()> {
+    let logger = Logger::initialize_rolling(path, max_bytes, max_archives)?;
+    install_log_facade(&logger);
+    LOGGER.set(logger).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            "Logger already initialized",
+        )
+    })
+}
+
+/// Registers `logger` as the `log` crate's global logger so `info!`/`warn!`
+/// calls from dependency crates (regex, reqwest, ...) flow into the same
+/// file, ring buffer, and channel as our own `log()` calls.
+fn install_log_facade(logger: &Logger) {
+    if log::set_boxed_logger(Box::new(logger.clone())).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+/// Adjusts how verbose the `log` facade is; defaults to `Info`.
+pub fn set_level_filter(filter: log::LevelFilter) {
+    log::set_max_level(filter);
+}
+
 pub fn global_logger() -> Option<Logger> {
     LOGGER.get().cloned()
 }
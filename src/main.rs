@@ -4,20 +4,40 @@ mod settings;
 mod steam;
 
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use backend::colored_name::ColoredName;
 use backend::dxvk;
+use backend::launch;
+use backend::progress::Progress;
+use backend::state::{ComponentState, installation_state};
 use backend::t7patch::{
     check_t7_patch_status, install_t7_patch, uninstall_t7_patch, update_t7patch_conf,
 };
+use backend::updates::{self, UpdateCheckResult};
+use backend::verify::{self, VerifyReport};
 use iced::executor;
 use iced::theme::{self, Palette};
-use iced::widget::{Space, button, column, container, row, scrollable, text, text_input, toggler};
+use iced::widget::{
+    Space, button, column, container, pick_list, progress_bar, row, scrollable, text, text_input,
+    toggler,
+};
 use iced::{
     Alignment, Application, Color, Command, Element, Length, Settings, Subscription, Theme, window,
 };
-use logging::{LogCategory, LogEntry, init_global_logger, log};
+use logging::{LogCategory, LogEntry, init_global_logger_rolling, log};
 use settings::{AppSettings, default_application_dir};
 
+/// Black Ops III's Steam app ID, used to auto-locate the install via
+/// `steam::find_game_dir` when no directory is saved in `AppSettings` yet.
+const BLACK_OPS_III_APP_ID: u32 = 311210;
+
+/// How many entries the Activity Log panel keeps, and so also how big
+/// `logging`'s ring buffer needs to be for `logging::query` to ever surface
+/// as much history as the panel displays.
+const LOG_ENTRY_LIMIT: usize = 500;
+
 fn main() -> iced::Result {
     let mut settings = Settings::default();
     settings.window = window::Settings {
@@ -41,6 +61,21 @@ struct PatchOpsApp {
     busy: bool,
     t7_status: String,
     dxvk_installed: bool,
+    dxvk_status: String,
+    dxvk_versions: Vec<dxvk::DxvkVersion>,
+    dxvk_selected_version: Option<String>,
+    runner_input: String,
+    prefix_input: String,
+    sandboxed_launch: bool,
+    progress_sender: flume::Sender<Progress>,
+    progress_receiver: flume::Receiver<Progress>,
+    current_progress: Option<Progress>,
+    last_verify_report: Option<VerifyReport>,
+    update_check: Option<UpdateCheckResult>,
+    /// Flipped by `Message::CancelInstall` and polled by the in-flight
+    /// download's `ChannelProgress`; reset before each new install so a
+    /// stale cancellation can't abort the next one.
+    cancel_flag: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,7 +88,8 @@ enum Message {
     PasswordChanged(String),
     FriendsOnlyToggled(bool),
     InstallPatch,
-    PatchInstalled(Result<(), String>),
+    PatchInstalled(Result<String, String>),
+    CancelInstall,
     UninstallPatch,
     PatchUninstalled(Result<(), String>),
     UpdateGamertag,
@@ -62,9 +98,28 @@ enum Message {
     PasswordUpdated(Result<(), String>),
     FriendsOnlyUpdated(Result<(), String>),
     InstallDxvk,
-    DxvkInstalled(Result<(), String>),
+    DxvkInstalled(Result<String, String>),
     UninstallDxvk,
     DxvkUninstalled(Result<(), String>),
+    DxvkReleasesFetched(Result<Vec<dxvk::DxvkVersion>, String>),
+    DxvkVersionSelected(String),
+    RunnerPathChanged(String),
+    BrowseRunner,
+    RunnerSelected(Option<PathBuf>),
+    PrefixPathChanged(String),
+    BrowsePrefix,
+    PrefixSelected(Option<PathBuf>),
+    SaveLaunchSettings,
+    SandboxToggled(bool),
+    LaunchGame,
+    GameLaunched(Result<(), String>),
+    ProgressUpdate(Progress),
+    VerifyGame,
+    GameVerified(Result<VerifyReport, String>),
+    RepairGame,
+    GameRepaired(Result<(), String>),
+    CheckForUpdates,
+    UpdatesChecked(Result<UpdateCheckResult, String>),
     LogReceived(LogEntry),
 }
 
@@ -79,17 +134,54 @@ impl Application for PatchOpsApp {
         let mod_dir = app_dir.join("BO3 Mod Files");
         std::fs::create_dir_all(&mod_dir).ok();
 
-        init_global_logger(app_dir.join("PatchOpsIII.log")).expect("Failed to initialise logger");
+        init_global_logger_rolling(app_dir.join("PatchOpsIII.log"), 5 * 1024 * 1024, 5)
+            .expect("Failed to initialise logger");
+        logging::set_ring_buffer_capacity(LOG_ENTRY_LIMIT);
+        // The Activity Log panel already renders every entry, so mirroring
+        // them to a stdout nobody's watching in this GUI app would just be
+        // wasted I/O; PATCHOPSIII_CONSOLE opts back in for terminal debugging,
+        // kept to warnings and above so routine Info lines don't flood it.
+        if std::env::var_os("PATCHOPSIII_CONSOLE").is_some() {
+            logging::enable_console();
+            logging::set_console_min_severity(LogCategory::Warning);
+        } else {
+            logging::disable_console();
+        }
         let (sender, receiver) = flume::unbounded();
         logging::set_channel(sender);
 
+        let mut log_entries = logging::query(&logging::RecordFilter {
+            limit: Some(LOG_ENTRY_LIMIT),
+            ..Default::default()
+        });
+        log_entries.reverse();
+
         let settings = AppSettings::load(&app_dir).unwrap_or_default();
         let game_dir_input = settings
             .game_directory
             .as_ref()
             .map(|p| p.to_string_lossy().to_string())
+            .or_else(|| {
+                steam::detect()
+                    .and_then(|paths| paths.game_dir(BLACK_OPS_III_APP_ID))
+                    .map(|p| p.to_string_lossy().to_string())
+            })
             .unwrap_or_else(default_game_path);
 
+        let (progress_sender, progress_receiver) = flume::unbounded();
+        let sandboxed_launch = settings.sandboxed_launch;
+
+        let runner_input = settings
+            .wine_runner_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let prefix_input = settings
+            .wine_prefix_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+
         let mut app = PatchOpsApp {
             app_dir,
             mod_dir,
@@ -98,14 +190,38 @@ impl Application for PatchOpsApp {
             gamertag_input: String::new(),
             password_input: String::new(),
             friends_only: false,
-            log_entries: Vec::new(),
+            log_entries,
             log_receiver: receiver,
             busy: false,
             t7_status: String::new(),
             dxvk_installed: false,
+            dxvk_status: String::new(),
+            dxvk_versions: Vec::new(),
+            dxvk_selected_version: None,
+            runner_input,
+            prefix_input,
+            sandboxed_launch,
+            progress_sender,
+            progress_receiver,
+            current_progress: None,
+            last_verify_report: None,
+            update_check: None,
+            cancel_flag: Arc::new(AtomicBool::new(false)),
         };
         app.refresh_state();
-        (app, Command::none())
+        let fetch_releases = Command::perform(
+            async move { dxvk::list_available().map_err(|e| e.to_string()) },
+            Message::DxvkReleasesFetched,
+        );
+        let current_t7_patch = app.settings.t7_patch_version.clone();
+        let current_dxvk = app.settings.dxvk_version.clone();
+        let check_updates = Command::perform(
+            async move {
+                updates::check_for_updates(current_t7_patch, current_dxvk).map_err(|e| e.to_string())
+            },
+            Message::UpdatesChecked,
+        );
+        (app, Command::batch([fetch_releases, check_updates]))
     }
 
     fn title(&self) -> String {
@@ -169,16 +285,48 @@ impl Application for PatchOpsApp {
                     return Command::none();
                 }
                 self.busy = true;
+                self.current_progress = None;
+                self.cancel_flag.store(false, Ordering::Relaxed);
                 let game_dir = PathBuf::from(self.game_dir_input.clone());
                 let mod_dir = self.mod_dir.clone();
+                let progress_sender = self.progress_sender.clone();
+                let cancel_flag = self.cancel_flag.clone();
                 Command::perform(
-                    async move { install_t7_patch(&game_dir, &mod_dir).map_err(|e| e.to_string()) },
+                    async move {
+                        install_t7_patch(
+                            &game_dir,
+                            &mod_dir,
+                            Some(&progress_sender),
+                            Some(cancel_flag.as_ref()),
+                        )
+                        .map_err(|e| e.to_string())
+                    },
                     Message::PatchInstalled,
                 )
             }
+            Message::CancelInstall => {
+                self.cancel_flag.store(true, Ordering::Relaxed);
+                log(LogCategory::Info, "Cancelling in-progress download...");
+                Command::none()
+            }
             Message::PatchInstalled(result) => {
                 self.busy = false;
-                report_result(result, "Installed T7 Patch");
+                self.current_progress = None;
+                match result {
+                    Ok(version) => {
+                        if !version.is_empty() {
+                            self.settings.t7_patch_version = Some(version);
+                            if let Err(err) = self.settings.save(&self.app_dir) {
+                                log(
+                                    LogCategory::Error,
+                                    format!("Failed to save T7 patch version: {err}"),
+                                );
+                            }
+                        }
+                        log(LogCategory::Success, "Installed T7 Patch");
+                    }
+                    Err(err) => log(LogCategory::Error, err),
+                }
                 self.refresh_state();
                 Command::none()
             }
@@ -197,6 +345,13 @@ impl Application for PatchOpsApp {
             Message::PatchUninstalled(result) => {
                 self.busy = false;
                 report_result(result, "Uninstalled T7 Patch");
+                self.settings.t7_patch_version = None;
+                if let Err(err) = self.settings.save(&self.app_dir) {
+                    log(
+                        LogCategory::Error,
+                        format!("Failed to clear T7 patch version: {err}"),
+                    );
+                }
                 self.refresh_state();
                 Command::none()
             }
@@ -206,7 +361,7 @@ impl Application for PatchOpsApp {
                 }
                 self.busy = true;
                 let game_dir = PathBuf::from(self.game_dir_input.clone());
-                let name = self.gamertag_input.clone();
+                let name = ColoredName::parse(&self.gamertag_input);
                 Command::perform(
                     async move {
                         update_t7patch_conf(&game_dir, Some(&name), None, None)
@@ -253,16 +408,58 @@ impl Application for PatchOpsApp {
                     return Command::none();
                 }
                 self.busy = true;
+                self.current_progress = None;
+                self.cancel_flag.store(false, Ordering::Relaxed);
                 let game_dir = PathBuf::from(self.game_dir_input.clone());
                 let mod_dir = self.mod_dir.clone();
+                let progress_sender = self.progress_sender.clone();
+                let cancel_flag = self.cancel_flag.clone();
+                let selected = self
+                    .dxvk_selected_version
+                    .as_ref()
+                    .and_then(|tag| self.dxvk_versions.iter().find(|v| &v.tag == tag).cloned());
                 Command::perform(
-                    async move { dxvk::install(&game_dir, &mod_dir).map_err(|e| e.to_string()) },
+                    async move {
+                        match selected {
+                            Some(version) => dxvk::install_version(
+                                &mod_dir,
+                                &version,
+                                Some(&progress_sender),
+                                Some(cancel_flag.as_ref()),
+                            )
+                            .and_then(|_| dxvk::activate(&game_dir, &mod_dir, &version))
+                            .map(|_| version.tag)
+                            .map_err(|e| e.to_string()),
+                            None => dxvk::install(
+                                &game_dir,
+                                &mod_dir,
+                                Some(&progress_sender),
+                                Some(cancel_flag.as_ref()),
+                            )
+                            .map_err(|e| e.to_string()),
+                        }
+                    },
                     Message::DxvkInstalled,
                 )
             }
             Message::DxvkInstalled(result) => {
                 self.busy = false;
-                report_result(result, "Installed DXVK-GPLAsync");
+                self.current_progress = None;
+                match result {
+                    Ok(tag) => {
+                        if !tag.is_empty() {
+                            self.settings.dxvk_version = Some(tag);
+                            if let Err(err) = self.settings.save(&self.app_dir) {
+                                log(
+                                    LogCategory::Error,
+                                    format!("Failed to save DXVK version: {err}"),
+                                );
+                            }
+                        }
+                        log(LogCategory::Success, "Installed DXVK-GPLAsync");
+                    }
+                    Err(err) => log(LogCategory::Error, err),
+                }
                 self.refresh_state();
                 Command::none()
             }
@@ -272,21 +469,212 @@ impl Application for PatchOpsApp {
                 }
                 self.busy = true;
                 let game_dir = PathBuf::from(self.game_dir_input.clone());
+                let mod_dir = self.mod_dir.clone();
                 Command::perform(
-                    async move { dxvk::uninstall(&game_dir).map_err(|e| e.to_string()) },
+                    async move { dxvk::uninstall(&game_dir, &mod_dir).map_err(|e| e.to_string()) },
                     Message::DxvkUninstalled,
                 )
             }
             Message::DxvkUninstalled(result) => {
                 self.busy = false;
                 report_result(result, "Uninstalled DXVK-GPLAsync");
+                self.settings.dxvk_version = None;
+                if let Err(err) = self.settings.save(&self.app_dir) {
+                    log(
+                        LogCategory::Error,
+                        format!("Failed to clear DXVK version: {err}"),
+                    );
+                }
                 self.refresh_state();
                 Command::none()
             }
+            Message::DxvkReleasesFetched(result) => {
+                match result {
+                    Ok(versions) => self.dxvk_versions = versions,
+                    Err(err) => log(
+                        LogCategory::Warning,
+                        format!("Failed to fetch DXVK-GPLAsync releases: {err}"),
+                    ),
+                }
+                Command::none()
+            }
+            Message::DxvkVersionSelected(tag) => {
+                self.dxvk_selected_version = Some(tag);
+                Command::none()
+            }
+            Message::RunnerPathChanged(value) => {
+                self.runner_input = value;
+                Command::none()
+            }
+            Message::BrowseRunner => Command::perform(select_file(), Message::RunnerSelected),
+            Message::RunnerSelected(selection) => {
+                if let Some(path) = selection {
+                    self.runner_input = path.to_string_lossy().to_string();
+                }
+                Command::none()
+            }
+            Message::PrefixPathChanged(value) => {
+                self.prefix_input = value;
+                Command::none()
+            }
+            Message::BrowsePrefix => Command::perform(select_directory(), Message::PrefixSelected),
+            Message::PrefixSelected(selection) => {
+                if let Some(path) = selection {
+                    self.prefix_input = path.to_string_lossy().to_string();
+                }
+                Command::none()
+            }
+            Message::SaveLaunchSettings => {
+                self.settings.wine_runner_path = Some(PathBuf::from(&self.runner_input));
+                self.settings.wine_prefix_path = Some(PathBuf::from(&self.prefix_input));
+                self.settings.sandboxed_launch = self.sandboxed_launch;
+                if let Err(err) = self.settings.save(&self.app_dir) {
+                    log(
+                        LogCategory::Error,
+                        format!("Failed to save launch settings: {err}"),
+                    );
+                } else {
+                    log(LogCategory::Success, "Saved launch settings");
+                }
+                Command::none()
+            }
+            Message::SandboxToggled(value) => {
+                self.sandboxed_launch = value;
+                Command::none()
+            }
+            Message::LaunchGame => {
+                if self.busy {
+                    return Command::none();
+                }
+                self.busy = true;
+                let game_dir = PathBuf::from(self.game_dir_input.clone());
+                let runner = PathBuf::from(self.runner_input.clone());
+                let prefix = PathBuf::from(self.prefix_input.clone());
+                let sandboxed = self.sandboxed_launch;
+                Command::perform(
+                    async move {
+                        launch::launch_game(&game_dir, &runner, &prefix, sandboxed)
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::GameLaunched,
+                )
+            }
+            Message::GameLaunched(result) => {
+                self.busy = false;
+                report_result(result, "Game session finished");
+                Command::none()
+            }
+            Message::ProgressUpdate(progress) => {
+                self.current_progress = Some(progress);
+                Command::none()
+            }
+            Message::VerifyGame => {
+                if self.busy {
+                    return Command::none();
+                }
+                self.busy = true;
+                let game_dir = PathBuf::from(self.game_dir_input.clone());
+                let mod_dir = self.mod_dir.clone();
+                Command::perform(
+                    async move {
+                        let manifest_path = verify::default_manifest_path(&mod_dir);
+                        let manifest = verify::load_manifest(&manifest_path).map_err(|e| e.to_string())?;
+                        verify::verify_game(&game_dir, &manifest).map_err(|e| e.to_string())
+                    },
+                    Message::GameVerified,
+                )
+            }
+            Message::GameVerified(result) => {
+                self.busy = false;
+                match result {
+                    Ok(report) => {
+                        if report.is_clean() {
+                            log(LogCategory::Success, "Game files verified, no issues found");
+                        } else {
+                            let failing = report.failing().count();
+                            log(
+                                LogCategory::Warning,
+                                format!("Game file verification found {} issue(s)", failing),
+                            );
+                        }
+                        self.last_verify_report = Some(report);
+                    }
+                    Err(err) => log(LogCategory::Error, err),
+                }
+                Command::none()
+            }
+            Message::RepairGame => {
+                if self.busy {
+                    return Command::none();
+                }
+                let Some(report) = self.last_verify_report.clone() else {
+                    log(LogCategory::Warning, "Run Verify Game Files before repairing");
+                    return Command::none();
+                };
+                self.busy = true;
+                let game_dir = PathBuf::from(self.game_dir_input.clone());
+                let mod_dir = self.mod_dir.clone();
+                Command::perform(
+                    async move {
+                        verify::repair(&game_dir, &mod_dir, &report).map_err(|e| e.to_string())
+                    },
+                    Message::GameRepaired,
+                )
+            }
+            Message::GameRepaired(result) => {
+                self.busy = false;
+                report_result(result, "Repaired game files");
+                Command::none()
+            }
+            Message::CheckForUpdates => {
+                let current_t7_patch = self.settings.t7_patch_version.clone();
+                let current_dxvk = self.settings.dxvk_version.clone();
+                Command::perform(
+                    async move {
+                        updates::check_for_updates(current_t7_patch, current_dxvk)
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::UpdatesChecked,
+                )
+            }
+            Message::UpdatesChecked(result) => {
+                match result {
+                    Ok(check) => {
+                        if check.t7_patch.update_available() {
+                            log(
+                                LogCategory::Warning,
+                                format!(
+                                    "T7 Patch update available: {} -> {}",
+                                    check.t7_patch.current.as_deref().unwrap_or("unknown"),
+                                    check.t7_patch.latest.as_deref().unwrap_or("unknown")
+                                ),
+                            );
+                        }
+                        if check.dxvk.update_available() {
+                            log(
+                                LogCategory::Warning,
+                                format!(
+                                    "DXVK-GPLAsync update available: {} -> {}",
+                                    check.dxvk.current.as_deref().unwrap_or("unknown"),
+                                    check.dxvk.latest.as_deref().unwrap_or("unknown")
+                                ),
+                            );
+                        }
+                        self.update_check = Some(check);
+                        self.refresh_state();
+                    }
+                    Err(err) => log(
+                        LogCategory::Warning,
+                        format!("Failed to check for updates: {err}"),
+                    ),
+                }
+                Command::none()
+            }
             Message::LogReceived(entry) => {
                 self.log_entries.push(entry);
-                if self.log_entries.len() > 500 {
-                    self.log_entries.drain(0..self.log_entries.len() - 500);
+                if self.log_entries.len() > LOG_ENTRY_LIMIT {
+                    self.log_entries
+                        .drain(0..self.log_entries.len() - LOG_ENTRY_LIMIT);
                 }
                 Command::none()
             }
@@ -297,10 +685,38 @@ impl Application for PatchOpsApp {
         let header = row![
             text("PatchOpsIII").size(32),
             iced::widget::Space::with_width(Length::Fill),
+            button("Check for Updates").on_press(Message::CheckForUpdates),
             button("Save Game Directory").on_press(Message::SaveGameDir)
         ]
+        .spacing(12)
         .align_items(Alignment::Center);
 
+        let update_banner: Element<Message> = match &self.update_check {
+            Some(check) if check.t7_patch.update_available() || check.dxvk.update_available() => {
+                let mut lines = Vec::new();
+                if check.t7_patch.update_available() {
+                    lines.push(format!(
+                        "T7 Patch: Update available: {} \u{2192} {}",
+                        check.t7_patch.current.as_deref().unwrap_or("unknown"),
+                        check.t7_patch.latest.as_deref().unwrap_or("unknown")
+                    ));
+                }
+                if check.dxvk.update_available() {
+                    lines.push(format!(
+                        "DXVK-GPLAsync: Update available: {} \u{2192} {}",
+                        check.dxvk.current.as_deref().unwrap_or("unknown"),
+                        check.dxvk.latest.as_deref().unwrap_or("unknown")
+                    ));
+                }
+                column(lines.into_iter().map(|line| text(line).size(16).into()).collect())
+                    .spacing(4)
+                    .padding(16)
+                    .style(card_style())
+                    .into()
+            }
+            _ => Space::with_height(Length::Shrink).into(),
+        };
+
         let game_dir_section = column![
             text("Game Directory").size(24),
             row![
@@ -354,21 +770,62 @@ impl Application for PatchOpsApp {
         .padding(16)
         .style(card_style());
 
+        let dxvk_version_labels: Vec<String> =
+            self.dxvk_versions.iter().map(|v| v.tag.clone()).collect();
         let dxvk_section = column![
             text("DXVK-GPLAsync").size(24),
-            text(if self.dxvk_installed {
-                "DXVK-GPLAsync is installed"
-            } else {
-                "DXVK-GPLAsync is not installed"
-            })
-            .size(16),
-            if self.dxvk_installed {
-                button("Uninstall DXVK-GPLAsync").on_press(Message::UninstallDxvk)
-            } else {
-                button("Install DXVK-GPLAsync")
-                    .on_press(Message::InstallDxvk)
-                    .style(theme::Button::Primary)
-            }
+            text(&self.dxvk_status).size(16),
+            row![
+                pick_list(
+                    dxvk_version_labels,
+                    self.dxvk_selected_version.clone(),
+                    Message::DxvkVersionSelected
+                )
+                .placeholder("Latest release")
+                .padding(10),
+                if self.dxvk_installed {
+                    button("Uninstall DXVK-GPLAsync").on_press(Message::UninstallDxvk)
+                } else {
+                    button("Install DXVK-GPLAsync")
+                        .on_press(Message::InstallDxvk)
+                        .style(theme::Button::Primary)
+                }
+            ]
+            .spacing(12)
+        ]
+        .spacing(12)
+        .padding(16)
+        .style(card_style());
+
+        let launch_section = column![
+            text("Launch").size(24),
+            row![
+                text_input("Path to wine/proton runner", &self.runner_input)
+                    .on_input(Message::RunnerPathChanged)
+                    .padding(10)
+                    .width(Length::Fill),
+                button("Browse...").on_press(Message::BrowseRunner)
+            ]
+            .spacing(12),
+            row![
+                text_input("Wine prefix directory", &self.prefix_input)
+                    .on_input(Message::PrefixPathChanged)
+                    .padding(10)
+                    .width(Length::Fill),
+                button("Browse...").on_press(Message::BrowsePrefix)
+            ]
+            .spacing(12),
+            row![
+                button("Save Launch Settings").on_press(Message::SaveLaunchSettings),
+                button("Play").on_press(Message::LaunchGame).style(theme::Button::Primary),
+                toggler(
+                    "Run game sandboxed",
+                    self.sandboxed_launch,
+                    Message::SandboxToggled
+                )
+            ]
+            .spacing(12)
+            .align_items(Alignment::Center)
         ]
         .spacing(12)
         .padding(16)
@@ -386,6 +843,57 @@ impl Application for PatchOpsApp {
                 .size(16)
         });
 
+        let verify_status = match &self.last_verify_report {
+            Some(report) if report.is_clean() => "All tracked files verified".to_string(),
+            Some(report) => format!("{} file(s) need attention", report.failing().count()),
+            None => "Not yet verified".to_string(),
+        };
+        let verify_section = column![
+            text("Game Integrity").size(24),
+            text(verify_status).size(16),
+            row![
+                button("Verify Game Files").on_press(Message::VerifyGame),
+                button("Repair Game Files").on_press(Message::RepairGame)
+            ]
+            .spacing(12)
+        ]
+        .spacing(12)
+        .padding(16)
+        .style(card_style());
+
+        let progress_section: Element<Message> = match &self.current_progress {
+            Some(progress) => {
+                let fraction = if progress.total > 0 {
+                    progress.current as f32 / progress.total as f32
+                } else {
+                    0.0
+                };
+                let status = if progress.total > 0 {
+                    format!(
+                        "{}: {:.0}% ({:.2} of {:.2} MB)",
+                        progress.label,
+                        fraction * 100.0,
+                        progress.current as f64 / 1_000_000.0,
+                        progress.total as f64 / 1_000_000.0
+                    )
+                } else {
+                    format!("{}: {} bytes", progress.label, progress.current)
+                };
+                column![
+                    text(status).size(16),
+                    progress_bar(0.0..=1.0, fraction),
+                    button("Cancel")
+                        .on_press(Message::CancelInstall)
+                        .style(theme::Button::Destructive)
+                ]
+                .spacing(8)
+                .padding(16)
+                .style(card_style())
+                .into()
+            }
+            None => Space::with_height(Length::Shrink).into(),
+        };
+
         let log_panel = column![
             text("Activity Log").size(24),
             scrollable(column(log_entries).spacing(8))
@@ -396,10 +904,18 @@ impl Application for PatchOpsApp {
 
         let layout = column![
             header,
+            update_banner,
             row![
-                column![game_dir_section, t7_section, dxvk_section]
-                    .spacing(16)
-                    .width(Length::FillPortion(2)),
+                column![
+                    game_dir_section,
+                    t7_section,
+                    dxvk_section,
+                    launch_section,
+                    verify_section,
+                    progress_section
+                ]
+                .spacing(16)
+                .width(Length::FillPortion(2)),
                 log_panel.width(Length::FillPortion(1))
             ]
             .spacing(16)
@@ -425,7 +941,7 @@ impl Application for PatchOpsApp {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        iced::subscription::unfold(
+        let log_stream = iced::subscription::unfold(
             "log-stream",
             self.log_receiver.clone(),
             |receiver| async move {
@@ -434,34 +950,82 @@ impl Application for PatchOpsApp {
                     Err(_) => (None, receiver),
                 }
             },
-        )
+        );
+        let progress_stream = iced::subscription::unfold(
+            "progress-stream",
+            self.progress_receiver.clone(),
+            |receiver| async move {
+                match receiver.recv_async().await {
+                    Ok(progress) => (Some(Message::ProgressUpdate(progress)), receiver),
+                    Err(_) => (None, receiver),
+                }
+            },
+        );
+        Subscription::batch([log_stream, progress_stream])
     }
 }
 
 impl PatchOpsApp {
     fn refresh_state(&mut self) {
         let path = PathBuf::from(&self.game_dir_input);
-        match check_t7_patch_status(&path) {
-            Ok(status) => {
-                self.t7_status = status
-                    .gamertag
-                    .clone()
-                    .unwrap_or_else(|| "T7 Patch not detected".into());
-                if let Some(name) = status.plain_name {
-                    self.gamertag_input = name;
-                }
-                if let Some(password) = status.password {
-                    self.password_input = password;
+        let conf_status = check_t7_patch_status(&path);
+        if let Ok(status) = &conf_status {
+            if let Some(parsed) = status.parsed_name.clone() {
+                self.gamertag_input = parsed.plain();
+            }
+            if let Some(password) = status.password.clone() {
+                self.password_input = password;
+            }
+            if let Some(flag) = status.friends_only {
+                self.friends_only = flag;
+            }
+        }
+
+        let latest_t7 = self
+            .update_check
+            .as_ref()
+            .and_then(|check| check.t7_patch.latest.as_deref());
+        let latest_dxvk = self
+            .update_check
+            .as_ref()
+            .and_then(|check| check.dxvk.latest.as_deref());
+        let state = installation_state(
+            &path,
+            &self.mod_dir,
+            self.settings.t7_patch_version.as_deref(),
+            latest_t7,
+            latest_dxvk,
+        );
+        self.t7_status = if conf_status.is_err() {
+            "Unable to read t7patch.conf".into()
+        } else {
+            match &state.t7_patch {
+                ComponentState::NotInstalled => "T7 Patch not detected".into(),
+                ComponentState::Installed { version } if version.is_empty() => {
+                    "T7 Patch is installed".into()
                 }
-                if let Some(flag) = status.friends_only {
-                    self.friends_only = flag;
+                ComponentState::Installed { version } => format!("T7 Patch {} installed", version),
+                ComponentState::UpdateAvailable { current, .. } => {
+                    format!("T7 Patch {} installed (update available)", current)
                 }
+                ComponentState::FilesMissing(_) => "T7 Patch partially installed".into(),
             }
-            Err(_) => {
-                self.t7_status = "Unable to read t7patch.conf".into();
+        };
+
+        self.dxvk_installed = !matches!(state.dxvk, ComponentState::NotInstalled);
+        self.dxvk_status = match &state.dxvk {
+            ComponentState::NotInstalled => "DXVK-GPLAsync is not installed".into(),
+            ComponentState::Installed { version } => {
+                format!("DXVK-GPLAsync {} installed", version)
             }
-        }
-        self.dxvk_installed = dxvk::is_installed(&path);
+            ComponentState::UpdateAvailable { current, latest } => {
+                format!(
+                    "DXVK-GPLAsync {} installed ({} available)",
+                    current, latest
+                )
+            }
+            ComponentState::FilesMissing(_) => "DXVK-GPLAsync partially installed".into(),
+        };
     }
 }
 
@@ -504,3 +1068,7 @@ fn default_game_path() -> String {
 fn select_directory() -> impl std::future::Future<Output = Option<PathBuf>> {
     async move { rfd::FileDialog::new().pick_folder() }
 }
+
+fn select_file() -> impl std::future::Future<Output = Option<PathBuf>> {
+    async move { rfd::FileDialog::new().pick_file() }
+}
@@ -10,6 +10,11 @@ const SETTINGS_FILE: &str = "PatchOpsIII_settings.json";
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppSettings {
     pub game_directory: Option<PathBuf>,
+    pub dxvk_version: Option<String>,
+    pub t7_patch_version: Option<String>,
+    pub wine_runner_path: Option<PathBuf>,
+    pub wine_prefix_path: Option<PathBuf>,
+    pub sandboxed_launch: bool,
 }
 
 impl AppSettings {
@@ -110,6 +110,152 @@ fn detect_macos() -> Result<SteamPaths> {
     anyhow::bail!("macos detection invoked on non-macos target")
 }
 
+/// A single `"key" "value"` or `"key" { ... }` VDF entry. Steam's VDF files
+/// only ever nest quoted keys and quoted string leaves, so this is all the
+/// structure `libraryfolders.vdf` and `appmanifest_*.acf` need.
+#[derive(Debug, Clone)]
+enum VdfValue {
+    Str(String),
+    Obj(Vec<(String, VdfValue)>),
+}
+
+fn skip_vdf_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn read_vdf_quoted(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    skip_vdf_whitespace(chars);
+    if chars.peek() != Some(&'"') {
+        return None;
+    }
+    chars.next();
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    value.push(escaped);
+                }
+            }
+            '"' => return Some(value),
+            other => value.push(other),
+        }
+    }
+    Some(value)
+}
+
+fn parse_vdf_object(chars: &mut std::iter::Peekable<std::str::Chars>) -> Vec<(String, VdfValue)> {
+    let mut entries = Vec::new();
+    loop {
+        skip_vdf_whitespace(chars);
+        match chars.peek() {
+            None => break,
+            Some('}') => {
+                chars.next();
+                break;
+            }
+            Some('"') => {
+                let Some(key) = read_vdf_quoted(chars) else {
+                    break;
+                };
+                skip_vdf_whitespace(chars);
+                match chars.peek() {
+                    Some('{') => {
+                        chars.next();
+                        entries.push((key, VdfValue::Obj(parse_vdf_object(chars))));
+                    }
+                    Some('"') => {
+                        let value = read_vdf_quoted(chars).unwrap_or_default();
+                        entries.push((key, VdfValue::Str(value)));
+                    }
+                    _ => break,
+                }
+            }
+            Some(_) => {
+                chars.next();
+            }
+        }
+    }
+    entries
+}
+
+fn parse_vdf(input: &str) -> Vec<(String, VdfValue)> {
+    parse_vdf_object(&mut input.chars().peekable())
+}
+
+fn find_vdf_value<'a>(entries: &'a [(String, VdfValue)], key: &str) -> Option<&'a VdfValue> {
+    entries
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+/// Searches for a string-valued leaf named `key`, checking each level's own
+/// entries before descending into its nested objects in order, used to
+/// pull `installdir` out of an `.acf` manifest without modelling its full
+/// (much larger) schema.
+fn find_vdf_string(entries: &[(String, VdfValue)], key: &str) -> Option<String> {
+    for (k, v) in entries {
+        if k.eq_ignore_ascii_case(key) {
+            if let VdfValue::Str(s) = v {
+                return Some(s.clone());
+            }
+        }
+    }
+    for (_, v) in entries {
+        if let VdfValue::Obj(obj) = v {
+            if let Some(found) = find_vdf_string(obj, key) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+/// Reads `steamapps/libraryfolders.vdf` from the Steam root (the parent of
+/// `userdata`) and checks each listed library for an `appmanifest_<app_id>.acf`,
+/// returning `<library>/steamapps/common/<installdir>` for the first one found.
+/// Lets callers locate a game without a user-supplied path even when Steam
+/// libraries are spread across multiple drives.
+pub fn find_game_dir(paths: &SteamPaths, app_id: u32) -> Option<PathBuf> {
+    let steam_root = paths.userdata.parent()?;
+    let libraryfolders_path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    let data = std::fs::read_to_string(&libraryfolders_path).ok()?;
+    let root = parse_vdf(&data);
+    let VdfValue::Obj(libraries) = find_vdf_value(&root, "libraryfolders")? else {
+        return None;
+    };
+
+    for (_, library) in libraries {
+        let VdfValue::Obj(fields) = library else {
+            continue;
+        };
+        let Some(VdfValue::Str(library_path)) = find_vdf_value(fields, "path") else {
+            continue;
+        };
+        let steamapps = Path::new(library_path).join("steamapps");
+        let manifest_path = steamapps.join(format!("appmanifest_{}.acf", app_id));
+        let Ok(manifest_data) = std::fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+        let manifest = parse_vdf(&manifest_data);
+        if let Some(installdir) = find_vdf_string(&manifest, "installdir") {
+            return Some(steamapps.join("common").join(installdir));
+        }
+    }
+    None
+}
+
+impl SteamPaths {
+    /// Convenience wrapper around `find_game_dir` for call sites that already
+    /// hold a `SteamPaths`.
+    pub fn game_dir(&self, app_id: u32) -> Option<PathBuf> {
+        find_game_dir(self, app_id)
+    }
+}
+
 pub fn find_user_id(paths: &SteamPaths) -> Option<String> {
     let entries = std::fs::read_dir(&paths.userdata).ok()?;
     for entry in entries.flatten() {